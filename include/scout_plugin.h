@@ -0,0 +1,65 @@
+/*
+ * Native plugin interface for scout.
+ *
+ * Build a shared library exporting the two functions at the end of this
+ * file and put it in ~/.local/share/scout/plugins. Strings are UTF-8 and
+ * NUL-terminated. src/plugins.rs holds the same definitions; its tests
+ * compile this header to check that the two agree.
+ */
+#ifndef SCOUT_PLUGIN_H
+#define SCOUT_PLUGIN_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Bumped whenever the structs below change incompatibly. */
+#define SCOUT_ABI_VERSION 1
+
+typedef struct _GtkBox GtkBox;
+
+/* A result row. */
+typedef struct {
+    const char *title;
+    const char *subtitle;      /* may be NULL */
+    const char *icon;          /* theme icon name or image path; may be NULL */
+    const char *const *action; /* NULL-terminated argv run on Enter, or NULL */
+    const char *copy;          /* text copied on Enter when there is no action */
+    const char *render_data;   /* passed to render_row; NULL for a standard row */
+} ScoutItem;
+
+typedef struct {
+    const ScoutItem *items;
+    size_t len;
+} ScoutItemList;
+
+typedef struct {
+    uint32_t abi_version; /* SCOUT_ABI_VERSION */
+    const char *name;
+    const char *prefix;   /* e.g. "np "; NULL if the plugin only renders */
+
+    /* Up to max_results items for the query without the prefix, or NULL.
+     * The list is handed back to free_items once read. */
+    ScoutItemList *(*search)(const char *query, uint32_t max_results);
+    void (*free_items)(ScoutItemList *items);
+
+    /* Fills the row's horizontal box for an item that set render_data.
+     * Returning false draws the standard row instead. May be NULL. */
+    bool (*render_row)(const char *render_data, GtkBox *container);
+} ScoutPlugin;
+
+/* Must return SCOUT_ABI_VERSION. */
+uint32_t scout_plugin_abi_version(void);
+
+/* Static data that lives as long as the library; scout never frees it. */
+const ScoutPlugin *scout_plugin_register(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SCOUT_PLUGIN_H */
@@ -0,0 +1,135 @@
+use std::path::Path;
+use gio::prelude::AppInfoExt;
+use gio::{AppInfo, DesktopAppInfo};
+use glib::Cast;
+use crate::config::{self, Config};
+use crate::entry::{Entry, EntryKind, PanelAction};
+use crate::gpu;
+use crate::launcher;
+
+/// Rows of the actions panel that Tab opens on `entry`, or `None` when it has
+/// nothing beyond Enter and Shift+Enter.
+pub fn panel(entry: &Entry) -> Option<Vec<Entry>> {
+    let row = |action: PanelAction, title: String| Entry::panel_action(entry.clone(), action, title);
+    let mut rows = match &entry.kind {
+        EntryKind::App(app) => app_rows(app, row),
+        EntryKind::Result(_) => {
+            let mut rows = vec![row(PanelAction::Copy(entry.title.clone()), "Copy".to_string())];
+            if let Some(hex) = hex(&entry.title) {
+                rows.push(row(PanelAction::Copy(hex.clone()), format!("Copy as hex ({hex})")));
+            }
+            rows
+        }
+        EntryKind::Action(_) => vec![row(PanelAction::Confirm, format!("Confirm {}", entry.title.to_lowercase()))],
+        _ => return None,
+    };
+    rows.push(Entry::cancel());
+    Some(rows)
+}
+
+fn app_rows(app: &AppInfo, row: impl Fn(PanelAction, String) -> Entry) -> Vec<Entry> {
+    let mut rows = vec![
+        row(PanelAction::Launch, "Launch".to_string()),
+        row(PanelAction::LaunchInTerminal, "Launch in terminal".to_string()),
+    ];
+    if gpu::discrete(Path::new(gpu::SYSFS_ROOT)).is_some() {
+        rows.push(row(PanelAction::LaunchOnDiscreteGpu, "Launch on discrete GPU".to_string()));
+    }
+    if let Some(dai) = app.downcast_ref::<DesktopAppInfo>() {
+        for action in dai.list_actions() {
+            rows.push(row(PanelAction::DesktopAction(action.to_string()), dai.action_name(&action).to_string()));
+        }
+        if dai.filename().is_some() {
+            rows.push(row(PanelAction::OpenDesktopFile, "Open .desktop file".to_string()));
+        }
+    }
+    if app.commandline().is_some() {
+        rows.push(row(PanelAction::CopyCommandLine, "Copy command line".to_string()));
+    }
+    rows.push(row(PanelAction::Hide, "Hide from results".to_string()));
+    rows.push(row(PanelAction::Pin, "Pin to the start view".to_string()));
+    rows
+}
+
+/// Performs `action` from the panel opened on `target`.
+pub fn run(target: &Entry, action: &PanelAction, cfg: &Config) -> Result<(), String> {
+    match action {
+        PanelAction::Confirm => launcher::activate(target, cfg),
+        PanelAction::Copy(text) => {
+            launcher::copy_to_clipboard(text);
+            Ok(())
+        }
+        PanelAction::Hide | PanelAction::Pin => {
+            let key = if *action == PanelAction::Hide { "hidden_apps" } else { "pinned" };
+            let id = target.app_id().ok_or_else(|| format!("'{}' has no id to save", target.title))?;
+            config::add_to_list(key, &id).map_err(|e| format!("Failed to add '{}' to {}: {}", id, key, e))
+        }
+        _ => {
+            let EntryKind::App(app) = &target.kind else { return Ok(()) };
+            run_app_action(app, action, cfg)
+        }
+    }
+}
+
+fn run_app_action(app: &AppInfo, action: &PanelAction, cfg: &Config) -> Result<(), String> {
+    let dai = app.downcast_ref::<DesktopAppInfo>();
+    match action {
+        PanelAction::LaunchInTerminal => launcher::launch_in_terminal(app, cfg),
+        PanelAction::LaunchOnDiscreteGpu => launcher::launch_on_discrete_gpu(app, cfg),
+        PanelAction::DesktopAction(name) => {
+            let dai = dai.ok_or_else(|| format!("'{}' has no desktop file", app.name()))?;
+            dai.launch_action(name, Some(&gio::AppLaunchContext::new()));
+            Ok(())
+        }
+        PanelAction::OpenDesktopFile => match dai.and_then(|dai| dai.filename()) {
+            Some(path) => launcher::open_path(&path),
+            None => Err(format!("'{}' has no desktop file", app.name())),
+        },
+        PanelAction::CopyCommandLine => {
+            if let Some(commandline) = app.commandline() {
+                launcher::copy_to_clipboard(&commandline.to_string_lossy());
+            }
+            Ok(())
+        }
+        _ => launcher::launch_app(app, cfg),
+    }
+}
+
+/// A whole-number calculator result in hexadecimal, e.g. `255` as `0xff`.
+fn hex(value: &str) -> Option<String> {
+    let value: f64 = value.parse().ok()?;
+    if value.fract() != 0.0 || value.abs() >= i64::MAX as f64 {
+        return None;
+    }
+    let value = value as i64;
+    let sign = if value < 0 { "-" } else { "" };
+    Some(format!("{sign}0x{:x}", value.unsigned_abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::SystemAction;
+
+    #[test]
+    fn results_offer_hex_only_for_whole_numbers() {
+        assert_eq!(hex("255").as_deref(), Some("0xff"));
+        assert_eq!(hex("-16").as_deref(), Some("-0x10"));
+        assert_eq!(hex("0.5"), None);
+        assert_eq!(hex("inf"), None);
+
+        let rows = panel(&Entry::math_result(255.0)).unwrap();
+        let titles: Vec<&str> = rows.iter().map(|row| row.title.as_str()).collect();
+        assert_eq!(titles, ["Copy", "Copy as hex (0xff)", "Cancel"]);
+        assert!(matches!(&rows[1].kind, EntryKind::PanelAction { action: PanelAction::Copy(text), .. } if text == "0xff"));
+    }
+
+    #[test]
+    fn power_actions_ask_for_confirmation() {
+        let rows = panel(&Entry::system_action(SystemAction::Shutdown)).unwrap();
+        assert_eq!(rows[0].title, "Confirm shutdown");
+        assert!(matches!(&rows[0].kind, EntryKind::PanelAction { action: PanelAction::Confirm, target } if target.title == "Shutdown"));
+        assert!(matches!(rows[1].kind, EntryKind::Cancel));
+        assert!(panel(&Entry::math_hint("incomplete".to_string())).is_none());
+    }
+}
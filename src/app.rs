@@ -1,20 +1,57 @@
+use std::cell::Cell;
 use std::rc::Rc;
 use gio::prelude::{ApplicationExt, ApplicationExtManual};
 use gtk::Application;
-use crate::config::Config;
+use crate::config::{self, Config};
+use crate::lua::LuaHooks;
+use crate::plugins::Plugins;
+use crate::providers::clipboard::{self, ClipboardStore};
 use crate::ui::build_ui;
 
+/// Keeps scout running in the background: the window is closed instead of
+/// quitting, later `scout` invocations reopen it, and the clipboard is watched.
+const DAEMON_FLAG: &str = "--daemon";
+
 pub fn run(cfg: Rc<Config>) {
+    let mut args: Vec<String> = std::env::args().collect();
+    let daemon = args.iter().any(|arg| arg == DAEMON_FLAG);
+    args.retain(|arg| arg != DAEMON_FLAG);
+
     let app = Application::new(Some("com.scout"), Default::default());
+    let clipboard_store = Rc::new(ClipboardStore::load(&cfg.clipboard_history));
+    let plugins = Rc::new(Plugins::load_all());
+    let lua = Rc::new(LuaHooks::load(&cfg));
 
+    if daemon {
+        let clipboard_store = clipboard_store.clone();
+        app.connect_startup(move |app| {
+            // Held for the life of the process.
+            std::mem::forget(app.hold());
+            clipboard::watch(clipboard_store.clone());
+        });
+    }
+
+    // The daemon starts hidden; only activations from later invocations show the window.
+    let skip_activate = Cell::new(daemon);
     let cfg_clone = cfg.clone();
     app.connect_activate(move|app| {
-        let cfg_inner = cfg_clone.clone();
-        if let Err(e) = build_ui(app, cfg_inner) {
+        if skip_activate.replace(false) {
+            return;
+        }
+        // The daemon re-reads the config so edits, and rows hidden with Ctrl+Delete, apply to the next window.
+        let cfg_inner = if daemon {
+            config::load_or_create().map(Rc::new).unwrap_or_else(|e| {
+                eprintln!("Failed to reload config: {e}");
+                cfg_clone.clone()
+            })
+        } else {
+            cfg_clone.clone()
+        };
+        if let Err(e) = build_ui(app, cfg_inner, clipboard_store.clone(), plugins.clone(), lua.clone()) {
             eprintln!("UI error: {e}");
             app.quit();
         }
     });
 
-    app.run();
-}
\ No newline at end of file
+    app.run_with_args(&args);
+}
@@ -1,6 +1,9 @@
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
-use std::{fs, io, path::PathBuf};
+use std::collections::HashMap;
+use std::{fs, io, path::Path, path::PathBuf};
+use toml_edit::DocumentMut;
+use crate::glob::glob_match;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -24,6 +27,112 @@ pub struct Config {
 
     #[serde(default = "default_icon_size")]
     pub icon_size: i32,
+
+    #[serde(default)]
+    pub files: FileSearch,
+
+    #[serde(default)]
+    pub path_executables: bool,
+
+    #[serde(default = "default_search_engines")]
+    pub search_engines: Vec<SearchEngine>,
+
+    #[serde(default)]
+    pub clipboard_history: ClipboardHistory,
+
+    /// Switch to an app's open window instead of starting another instance.
+    #[serde(default)]
+    pub focus_existing: bool,
+
+    /// Per-application settings keyed by desktop file id, e.g. `"firefox.desktop"`.
+    #[serde(default)]
+    pub apps: HashMap<String, AppSettings>,
+
+    /// External programs that answer queries starting with their prefix.
+    #[serde(default)]
+    pub script_providers: Vec<ScriptProvider>,
+
+    /// Exact queries that jump to an app, e.g. `ff = "firefox.desktop"`.
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+
+    /// Desktop file ids listed first, in this order, before anything is typed.
+    #[serde(default)]
+    pub pinned: Vec<String>,
+
+    /// Desktop file ids or globs (e.g. `"avahi-*"`) left out of the results.
+    #[serde(default)]
+    pub hidden_apps: Vec<String>,
+
+    /// Apps in any of these desktop categories (e.g. `"Java"`) are left out.
+    #[serde(default)]
+    pub hidden_categories: Vec<String>,
+
+    /// List shutdown, restart, sleep and hibernate among the apps.
+    #[serde(default = "default_show_system_actions")]
+    pub show_system_actions: bool,
+
+    /// Start each launched app in its own transient systemd scope instead of scout's cgroup.
+    #[serde(default)]
+    pub systemd_scopes: bool,
+}
+
+impl Config {
+    /// Settings for the app with desktop file id `id`; the `.desktop` suffix may
+    /// be left out of the config key.
+    pub fn app_settings(&self, id: &str) -> Option<&AppSettings> {
+        self.apps
+            .get(id)
+            .or_else(|| self.apps.get(id.strip_suffix(".desktop")?))
+    }
+
+    /// Whether `hidden_apps` matches `id`, with or without its `.desktop` suffix.
+    pub fn hides_app(&self, id: &str) -> bool {
+        let short = id.strip_suffix(".desktop");
+        self.hidden_apps
+            .iter()
+            .any(|pattern| glob_match(pattern, id) || short.is_some_and(|short| glob_match(pattern, short)))
+    }
+
+    /// Whether any of `categories`, a desktop file's `;`-separated `Categories`, is hidden.
+    pub fn hides_categories(&self, categories: &str) -> bool {
+        categories
+            .split(';')
+            .any(|category| self.hidden_categories.iter().any(|hidden| hidden.eq_ignore_ascii_case(category)))
+    }
+
+    pub fn focus_existing_for(&self, id: Option<&str>) -> bool {
+        id.and_then(|id| self.app_settings(id))
+            .and_then(|settings| settings.focus_existing)
+            .unwrap_or(self.focus_existing)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppSettings {
+    /// Overrides the global `focus_existing` for this app.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focus_existing: Option<bool>,
+
+    /// Variables set for the app, e.g. `{ GDK_SCALE = "2" }`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+
+    /// Variables removed from the app's environment.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unset_env: Vec<String>,
+
+    /// Arguments added to the desktop entry's command line.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+
+    /// Working directory, replacing the desktop entry's `Path=`; `~` is expanded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+
+    /// Overrides the desktop entry's `Terminal=`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub terminal: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,7 +177,114 @@ impl Default for Theme {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSearch {
+    #[serde(default = "default_file_roots")]
+    pub roots: Vec<String>,
+
+    #[serde(default = "default_file_max_depth")]
+    pub max_depth: usize,
+
+    #[serde(default = "default_file_ignore")]
+    pub ignore: Vec<String>,
+
+    #[serde(default)]
+    pub show_hidden: bool,
+
+    #[serde(default = "default_file_max_indexed")]
+    pub max_indexed: usize,
+}
+
+impl Default for FileSearch {
+    fn default() -> Self {
+        Self {
+            roots: default_file_roots(),
+            max_depth: default_file_max_depth(),
+            ignore: default_file_ignore(),
+            show_hidden: false,
+            max_indexed: default_file_max_indexed(),
+        }
+    }
+}
+
+/// Text clipboard history, recorded while scout runs with `--daemon`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardHistory {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    #[serde(default = "default_clipboard_max_items")]
+    pub max_items: usize,
+
+    /// Larger clippings are not recorded.
+    #[serde(default = "default_clipboard_max_bytes")]
+    pub max_bytes: usize,
+
+    /// Clipboard targets (glob patterns) that mark a copy as not to be recorded,
+    /// such as the hint password managers set on copied secrets.
+    #[serde(default = "default_clipboard_ignore_targets")]
+    pub ignore_targets: Vec<String>,
+
+    #[serde(default)]
+    pub persist: Persistence,
+}
+
+impl Default for ClipboardHistory {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_items: default_clipboard_max_items(),
+            max_bytes: default_clipboard_max_bytes(),
+            ignore_targets: default_clipboard_ignore_targets(),
+            persist: Persistence::default(),
+        }
+    }
+}
+
+/// Whether clipboard history outlives the daemon: not at all, or encrypted
+/// in scout's data directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Persistence {
+    #[default]
+    None,
+    Encrypted,
+}
+
+/// A program run through `sh -c` that answers queries starting with `prefix`,
+/// reading one JSON request per line on stdin and replying with one per line on stdout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptProvider {
+    pub prefix: String,
+    pub command: String,
+
+    /// How long to wait for a reply before giving up on the script and restarting it.
+    #[serde(default = "default_script_timeout_ms")]
+    pub timeout_ms: u64,
+
+    /// How long typing must pause before a query is sent.
+    #[serde(default = "default_script_debounce_ms")]
+    pub debounce_ms: u64,
+}
+
+/// A web search shortcut: typing `<keyword> <terms>` opens `url` with `{query}`
+/// replaced by the URL-encoded terms.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchEngine {
+    pub keyword: String,
+    pub name: String,
+    pub url: String,
+}
+
+impl SearchEngine {
+    fn new(keyword: &str, name: &str, url: &str) -> Self {
+        Self { keyword: keyword.to_string(), name: name.to_string(), url: url.to_string() }
+    }
+}
+
+fn default_true() -> bool { true }
 fn default_show_icons() -> bool { true }
+fn default_show_system_actions() -> bool { true }
 fn default_max_results() -> usize { 5 }
 fn default_font_size() -> u32 { 14 }
 fn default_terminal_emulator() -> String { "kitty".to_string() }
@@ -82,6 +298,31 @@ fn default_selection_color() -> String { "#1e46c9".to_string() }
 fn default_entry_min_height() -> u32 { 32 }
 fn default_entry_border_color() -> String { "#3a3a3a".to_string() }
 fn default_entry_border_radius() -> u32 { 4 }
+fn default_file_roots() -> Vec<String> { vec!["~".to_string()] }
+fn default_file_max_depth() -> usize { 6 }
+fn default_file_ignore() -> Vec<String> {
+    ["node_modules", "target", "__pycache__", "*.o", "*.pyc"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+fn default_file_max_indexed() -> usize { 100_000 }
+fn default_search_engines() -> Vec<SearchEngine> {
+    vec![
+        SearchEngine::new("g", "Google", "https://www.google.com/search?q={query}"),
+        SearchEngine::new("gh", "GitHub", "https://github.com/search?q={query}"),
+        SearchEngine::new("rs", "docs.rs", "https://docs.rs/releases/search?query={query}"),
+        SearchEngine::new("crates", "crates.io", "https://crates.io/search?q={query}"),
+    ]
+}
+
+fn default_clipboard_max_items() -> usize { 100 }
+fn default_clipboard_max_bytes() -> usize { 64 * 1024 }
+fn default_clipboard_ignore_targets() -> Vec<String> {
+    vec!["x-kde-passwordManagerHint".to_string()]
+}
+fn default_script_timeout_ms() -> u64 { 2000 }
+fn default_script_debounce_ms() -> u64 { 150 }
 
 impl Default for Config {
     fn default() -> Self {
@@ -93,16 +334,76 @@ impl Default for Config {
             window_width: default_window_width(),
             window_height: default_window_height(),
             icon_size: default_icon_size(),
+            files: FileSearch::default(),
+            path_executables: false,
+            search_engines: default_search_engines(),
+            clipboard_history: ClipboardHistory::default(),
+            focus_existing: false,
+            apps: HashMap::new(),
+            script_providers: Vec::new(),
+            aliases: HashMap::new(),
+            pinned: Vec::new(),
+            hidden_apps: Vec::new(),
+            hidden_categories: Vec::new(),
+            show_system_actions: default_show_system_actions(),
+            systemd_scopes: false,
         }
     }
 }
 
-fn config_path() -> io::Result<PathBuf> {
+fn project_dirs() -> io::Result<ProjectDirs> {
     // (qualifier, organization, application)
-    let proj = ProjectDirs::from("io", "daniel-curry", "scout")
-        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "cannot determine config dir"))?;
+    ProjectDirs::from("io", "daniel-curry", "scout")
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "cannot determine config dir"))
+}
+
+fn config_path() -> io::Result<PathBuf> {
+    config_file("config.toml")
+}
+
+/// Path of a file next to `config.toml` (e.g. `~/.config/scout/clipboard.key`).
+pub fn config_file(name: &str) -> io::Result<PathBuf> {
+    Ok(project_dirs()?.config_dir().join(name))
+}
+
+/// Path of a file in scout's cache directory (e.g. `~/.cache/scout`),
+/// creating the directory if needed.
+pub fn cache_path(name: &str) -> io::Result<PathBuf> {
+    let proj = project_dirs()?;
+    fs::create_dir_all(proj.cache_dir())?;
+    Ok(proj.cache_dir().join(name))
+}
+
+/// Path of a file in scout's data directory (e.g. `~/.local/share/scout`),
+/// creating the directory if needed.
+pub fn data_path(name: &str) -> io::Result<PathBuf> {
+    let proj = project_dirs()?;
+    fs::create_dir_all(proj.data_dir())?;
+    Ok(proj.data_dir().join(name))
+}
 
-    Ok(proj.config_dir().join("config.toml"))
+/// Adds `value` to the top-level string list `key` in `config.toml` (e.g. a row
+/// hidden from the window), keeping the rest of the file as the user wrote it.
+pub fn add_to_list(key: &str, value: &str) -> io::Result<()> {
+    add_to_list_in(&config_path()?, key, value)
+}
+
+fn add_to_list_in(path: &Path, key: &str, value: &str) -> io::Result<()> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    let mut doc: DocumentMut = raw.parse().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let list = doc
+        .entry(key)
+        .or_insert(toml_edit::value(toml_edit::Array::new()))
+        .as_array_mut()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("`{key}` is not a list")))?;
+    if !list.iter().any(|item| item.as_str() == Some(value)) {
+        list.push(value);
+    }
+    fs::write(path, doc.to_string())
 }
 
 pub fn load_or_create() -> io::Result<Config> {
@@ -141,6 +442,9 @@ mod tests {
         assert_eq!(config.window_width, 600);
         assert_eq!(config.window_height, 260);
         assert_eq!(config.icon_size, 32);
+        assert!(!config.path_executables);
+        assert_eq!(config.search_engines[0].keyword, "g");
+        assert_eq!(config.clipboard_history.persist, Persistence::None);
     }
 
     #[test]
@@ -231,6 +535,7 @@ mod tests {
             window_width: 700,
             window_height: 350,
             icon_size: 40,
+            ..Config::default()
         };
 
         let toml_str = toml::to_string_pretty(&config)
@@ -267,6 +572,161 @@ mod tests {
         assert_eq!(original.icon_size, deserialized.icon_size);
     }
 
+    #[test]
+    fn test_parse_file_search_toml() {
+        let toml_str = r#"
+        [files]
+        roots = ["~/src", "/srv"]
+        max_depth = 3
+        "#;
+
+        let config: Config = toml::from_str(toml_str).expect("Failed to parse TOML");
+        assert_eq!(config.files.roots, vec!["~/src", "/srv"]);
+        assert_eq!(config.files.max_depth, 3);
+        // Unset keys fall back to defaults
+        assert!(!config.files.show_hidden);
+        assert_eq!(config.files.max_indexed, 100_000);
+        assert!(config.files.ignore.contains(&"node_modules".to_string()));
+    }
+
+    #[test]
+    fn test_parse_search_engines_toml() {
+        let toml_str = r#"
+        [[search_engines]]
+        keyword = "ddg"
+        name = "DuckDuckGo"
+        url = "https://duckduckgo.com/?q={query}"
+        "#;
+
+        let config: Config = toml::from_str(toml_str).expect("Failed to parse TOML");
+        // A configured list replaces the defaults entirely
+        assert_eq!(config.search_engines.len(), 1);
+        assert_eq!(config.search_engines[0].keyword, "ddg");
+        assert_eq!(config.search_engines[0].name, "DuckDuckGo");
+    }
+
+    #[test]
+    fn test_parse_clipboard_history_toml() {
+        let toml_str = r#"
+        [clipboard_history]
+        max_items = 20
+        persist = "encrypted"
+        "#;
+
+        let config: Config = toml::from_str(toml_str).expect("Failed to parse TOML");
+        assert_eq!(config.clipboard_history.max_items, 20);
+        assert_eq!(config.clipboard_history.persist, Persistence::Encrypted);
+        assert!(config.clipboard_history.enabled);
+        assert_eq!(config.clipboard_history.ignore_targets, vec!["x-kde-passwordManagerHint"]);
+    }
+
+    #[test]
+    fn test_per_app_focus_existing_overrides_global() {
+        let toml_str = r#"
+        focus_existing = true
+
+        [apps."kitty.desktop"]
+        focus_existing = false
+
+        [apps.firefox]
+        "#;
+
+        let config: Config = toml::from_str(toml_str).expect("Failed to parse TOML");
+        assert!(!config.focus_existing_for(Some("kitty.desktop")));
+        // Keys may omit `.desktop`; unset fields fall back to the global option
+        assert!(config.focus_existing_for(Some("firefox.desktop")));
+        assert!(config.focus_existing_for(None));
+    }
+
+    #[test]
+    fn test_parse_per_app_launch_settings_toml() {
+        let toml_str = r#"
+        [apps.firefox]
+        env = { MOZ_ENABLE_WAYLAND = "1", GDK_SCALE = "2" }
+        unset_env = ["GTK_THEME"]
+        args = ["--private-window"]
+        cwd = "~/Downloads"
+
+        [apps."htop.desktop"]
+        terminal = false
+        "#;
+
+        let config: Config = toml::from_str(toml_str).expect("Failed to parse TOML");
+        let firefox = config.app_settings("firefox.desktop").unwrap();
+        assert_eq!(firefox.env.get("GDK_SCALE").map(String::as_str), Some("2"));
+        assert_eq!(firefox.unset_env, vec!["GTK_THEME"]);
+        assert_eq!(firefox.args, vec!["--private-window"]);
+        assert_eq!(firefox.cwd.as_deref(), Some("~/Downloads"));
+        assert_eq!(firefox.terminal, None);
+        assert_eq!(config.app_settings("htop.desktop").unwrap().terminal, Some(false));
+    }
+
+    #[test]
+    fn test_parse_aliases_and_pinned_toml() {
+        let toml_str = r#"
+        pinned = ["kitty.desktop", "firefox"]
+
+        [aliases]
+        ff = "firefox.desktop"
+        "#;
+
+        let config: Config = toml::from_str(toml_str).expect("Failed to parse TOML");
+        assert_eq!(config.aliases.get("ff").map(String::as_str), Some("firefox.desktop"));
+        assert_eq!(config.pinned, vec!["kitty.desktop", "firefox"]);
+        assert!(Config::default().aliases.is_empty());
+    }
+
+    #[test]
+    fn test_hidden_apps_and_categories() {
+        let toml_str = r#"
+        hidden_apps = ["avahi-*", "org.gnome.Extensions"]
+        hidden_categories = ["Java"]
+        show_system_actions = false
+        "#;
+
+        let config: Config = toml::from_str(toml_str).expect("Failed to parse TOML");
+        assert!(config.hides_app("avahi-discover.desktop"));
+        assert!(config.hides_app("org.gnome.Extensions.desktop"));
+        assert!(!config.hides_app("firefox.desktop"));
+        assert!(config.hides_categories("Settings;Java;"));
+        assert!(!config.hides_categories("Network;WebBrowser;"));
+        assert!(!config.show_system_actions);
+        assert!(Config::default().show_system_actions);
+    }
+
+    #[test]
+    fn test_add_to_list_keeps_the_rest_of_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "# my settings\nmax_results = 8\n\n[theme]\nfont_size = 12\n").unwrap();
+
+        add_to_list_in(&path, "hidden_apps", "avahi-discover.desktop").unwrap();
+        add_to_list_in(&path, "hidden_apps", "avahi-discover.desktop").unwrap();
+        add_to_list_in(&path, "hidden_apps", "bssh.desktop").unwrap();
+
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(raw.starts_with("# my settings\nmax_results = 8\n"));
+        let config: Config = toml::from_str(&raw).expect("Failed to parse TOML");
+        assert_eq!(config.hidden_apps, vec!["avahi-discover.desktop", "bssh.desktop"]);
+        assert_eq!(config.theme.font_size, 12);
+    }
+
+    #[test]
+    fn test_parse_script_providers_toml() {
+        let toml_str = r#"
+        [[script_providers]]
+        prefix = "pw "
+        command = "~/bin/passwords --json"
+        timeout_ms = 5000
+        "#;
+
+        let config: Config = toml::from_str(toml_str).expect("Failed to parse TOML");
+        assert_eq!(config.script_providers.len(), 1);
+        assert_eq!(config.script_providers[0].prefix, "pw ");
+        assert_eq!(config.script_providers[0].timeout_ms, 5000);
+        assert_eq!(config.script_providers[0].debounce_ms, 150);
+    }
+
     #[test]
     fn test_invalid_toml_syntax() {
         let invalid_toml = r#"
@@ -1,3 +1,5 @@
+use std::fmt;
+use std::path::PathBuf;
 use gio::AppInfo;
 
 #[derive(Clone, Debug)]
@@ -5,14 +7,183 @@ pub enum EntryKind {
     App(AppInfo),
     Action(SystemAction),
     Result(String),
+    Hint,
+    File(PathBuf),
+    Recent(RecentDoc),
+    OpenWith { app: AppInfo, uri: String },
+    /// An app to start with the files or URLs typed after its name.
+    AppWithArgs { app: AppInfo, args: Vec<String> },
+    Command { command: String, terminal: bool },
+    Executable(PathBuf),
+    Url(String),
+    Ssh(SshHost),
+    Emoji { glyph: String, skin_tones: bool },
+    Clipboard(String),
+    Window(OpenWindow),
+    Process(Process),
+    /// A signal to send once the user confirmed it.
+    Signal { process: Process, signal: i32 },
+    Unit(Unit),
+    UnitAction { unit: Unit, action: UnitAction },
+    Script(ScriptItem),
+    Plugin(PluginItem),
+    /// A row of the actions panel opened with Tab on `target`.
+    PanelAction { target: Box<Entry>, action: PanelAction },
+    /// Leaves a confirmation list and shows the query's results again.
+    Cancel,
 }
 
 #[derive(Clone, Debug)]
 pub struct Entry {
     pub title: String,
+    pub subtitle: Option<String>,
     pub kind: EntryKind,
 }
 
+/// A document from the GTK recent-files list and the application that last opened it.
+#[derive(Clone, Debug)]
+pub struct RecentDoc {
+    pub uri: String,
+    pub mime_type: String,
+    pub app: Option<AppInfo>,
+}
+
+/// A host to connect to with `ssh`, from `~/.ssh/config` or `known_hosts`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SshHost {
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+impl SshHost {
+    /// `--` ends ssh's options, so a host can't pass itself off as one.
+    pub fn argv(&self) -> Vec<String> {
+        let mut argv = vec!["ssh".to_string()];
+        if let Some(port) = self.port {
+            argv.push("-p".to_string());
+            argv.push(port.to_string());
+        }
+        argv.push("--".to_string());
+        argv.push(self.host.clone());
+        argv
+    }
+
+    /// The command as the user would type it, without the `--`.
+    pub fn command_line(&self) -> String {
+        match self.port {
+            Some(port) => format!("ssh -p {} {}", port, self.host),
+            None => format!("ssh {}", self.host),
+        }
+    }
+}
+
+impl fmt::Display for SshHost {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.port {
+            Some(port) => write!(f, "{}:{}", self.host, port),
+            None => write!(f, "{}", self.host),
+        }
+    }
+}
+
+/// A top-level window listed by the window manager.
+#[derive(Clone, Debug)]
+pub struct OpenWindow {
+    pub id: u32,
+    pub title: String,
+    /// The `WM_CLASS` instance and class names, e.g. `("code", "Code")`.
+    pub instance: String,
+    pub class: String,
+    pub pid: Option<u32>,
+    pub icon: Option<WindowIcon>,
+}
+
+/// Non-premultiplied RGBA pixels from `_NET_WM_ICON`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowIcon {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// A running process as listed from `/proc`.
+#[derive(Clone, Debug)]
+pub struct Process {
+    pub pid: u32,
+    pub name: String,
+    pub cmdline: String,
+    pub uid: u32,
+    pub user: String,
+    pub cpu_percent: f32,
+    pub rss_kib: u64,
+    /// Part of the system or session; killing it needs confirmation.
+    pub critical: bool,
+}
+
+/// A systemd user unit as listed by the service manager.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unit {
+    pub name: String,
+    pub description: String,
+    /// `active`, `inactive`, `failed`, …, and the unit type's finer state such as `running`.
+    pub active_state: String,
+    pub sub_state: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnitAction {
+    Start,
+    Stop,
+    Restart,
+    Logs,
+}
+
+/// A row returned by a script provider.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptItem {
+    /// An icon name from the theme, or a path to an image.
+    pub icon: Option<String>,
+    pub action: ScriptAction,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptAction {
+    /// Spawns this argv, detached.
+    Run(Vec<String>),
+    /// Puts this text on the clipboard.
+    Copy(String),
+}
+
+/// A row returned by a native plugin.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PluginItem {
+    /// Which loaded plugin returned it, to hand `render_data` back to.
+    pub plugin: usize,
+    pub icon: Option<String>,
+    /// Rows without an action are informational.
+    pub action: Option<ScriptAction>,
+    /// Set when the plugin draws the row itself.
+    pub render_data: Option<String>,
+}
+
+/// What the actions panel can do with a row.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PanelAction {
+    Launch,
+    LaunchInTerminal,
+    /// Launches with the PRIME offload environment for the discrete GPU.
+    LaunchOnDiscreteGpu,
+    /// One of the desktop file's `Actions=`, e.g. `new-private-window`.
+    DesktopAction(String),
+    OpenDesktopFile,
+    CopyCommandLine,
+    Hide,
+    Pin,
+    Copy(String),
+    /// Runs the row's default action once the user confirmed it.
+    Confirm,
+}
+
 #[derive(Clone, Debug)]
 pub enum SystemAction {
     Shutdown,
@@ -27,10 +198,22 @@ impl Entry {
         use gio::prelude::AppInfoExt;
         Self {
             title: app.display_name().to_string(),
+            subtitle: None,
             kind: EntryKind::App(app),
         }
     }
 
+    /// The id aliases and pins refer to: an app's desktop file id, or a `$PATH`
+    /// executable's file name.
+    pub fn app_id(&self) -> Option<String> {
+        use gio::prelude::AppInfoExt;
+        match &self.kind {
+            EntryKind::App(app) => app.id().map(|id| id.to_string()),
+            EntryKind::Executable(path) => Some(path.file_name()?.to_string_lossy().into_owned()),
+            _ => None,
+        }
+    }
+
     pub fn system_action(action: SystemAction) -> Self {
         let title = match &action {
             SystemAction::Shutdown => "Shutdown",
@@ -41,6 +224,7 @@ impl Entry {
 
         Self {
             title: title.to_string(),
+            subtitle: None,
             kind: EntryKind::Action(action),
         }
     }
@@ -48,7 +232,179 @@ impl Entry {
     pub fn math_result(result: f64) -> Self {
         Self {
             title: format!("{}", result),
+            subtitle: None,
             kind: EntryKind::Result(String::new()),
         }
     }
+
+    pub fn math_hint(hint: String) -> Self {
+        Self::hint(hint)
+    }
+
+    /// An informational row that does nothing when activated, e.g. a
+    /// script's message or an error.
+    pub fn hint(text: String) -> Self {
+        Self {
+            title: text,
+            subtitle: None,
+            kind: EntryKind::Hint,
+        }
+    }
+
+    pub fn file(path: PathBuf, title: String) -> Self {
+        Self {
+            title,
+            subtitle: None,
+            kind: EntryKind::File(path),
+        }
+    }
+
+    pub fn recent(doc: RecentDoc, title: String) -> Self {
+        Self {
+            title,
+            subtitle: None,
+            kind: EntryKind::Recent(doc),
+        }
+    }
+
+    pub fn open_with(app: AppInfo, uri: String, title: String) -> Self {
+        Self {
+            title,
+            subtitle: None,
+            kind: EntryKind::OpenWith { app, uri },
+        }
+    }
+
+    pub fn app_with_args(app: AppInfo, args: Vec<String>, title: String) -> Self {
+        Self {
+            title,
+            subtitle: Some(format!("Open {}", args.join(" "))),
+            kind: EntryKind::AppWithArgs { app, args },
+        }
+    }
+
+    pub fn command(command: String, terminal: bool) -> Self {
+        Self {
+            title: command.clone(),
+            subtitle: None,
+            kind: EntryKind::Command { command, terminal },
+        }
+    }
+
+    pub fn executable(path: PathBuf) -> Self {
+        Self {
+            title: path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+            subtitle: None,
+            kind: EntryKind::Executable(path),
+        }
+    }
+
+    pub fn url(url: String, title: String) -> Self {
+        Self {
+            title,
+            subtitle: None,
+            kind: EntryKind::Url(url),
+        }
+    }
+
+    pub fn ssh(host: SshHost) -> Self {
+        Self {
+            title: host.command_line(),
+            subtitle: None,
+            kind: EntryKind::Ssh(host),
+        }
+    }
+
+    pub fn emoji(glyph: String, name: String, skin_tones: bool) -> Self {
+        Self {
+            title: name,
+            subtitle: None,
+            kind: EntryKind::Emoji { glyph, skin_tones },
+        }
+    }
+
+    pub fn clipboard(text: String, title: String) -> Self {
+        Self {
+            title,
+            subtitle: None,
+            kind: EntryKind::Clipboard(text),
+        }
+    }
+
+    pub fn window(window: OpenWindow) -> Self {
+        Self {
+            title: window.title.clone(),
+            subtitle: None,
+            kind: EntryKind::Window(window),
+        }
+    }
+
+    pub fn process(process: Process) -> Self {
+        Self {
+            title: process.name.clone(),
+            subtitle: None,
+            kind: EntryKind::Process(process),
+        }
+    }
+
+    pub fn signal(process: Process, signal: i32, title: String) -> Self {
+        Self {
+            title,
+            subtitle: None,
+            kind: EntryKind::Signal { process, signal },
+        }
+    }
+
+    pub fn unit(unit: Unit) -> Self {
+        Self {
+            title: unit.name.clone(),
+            subtitle: None,
+            kind: EntryKind::Unit(unit),
+        }
+    }
+
+    pub fn unit_action(unit: Unit, action: UnitAction, title: String) -> Self {
+        Self {
+            title,
+            subtitle: None,
+            kind: EntryKind::UnitAction { unit, action },
+        }
+    }
+
+    pub fn script(item: ScriptItem, title: String) -> Self {
+        Self {
+            title,
+            subtitle: None,
+            kind: EntryKind::Script(item),
+        }
+    }
+
+    pub fn plugin(item: PluginItem, title: String) -> Self {
+        Self {
+            title,
+            subtitle: None,
+            kind: EntryKind::Plugin(item),
+        }
+    }
+
+    pub fn panel_action(target: Entry, action: PanelAction, title: String) -> Self {
+        Self {
+            title,
+            subtitle: None,
+            kind: EntryKind::PanelAction { target: Box::new(target), action },
+        }
+    }
+
+    pub fn cancel() -> Self {
+        Self {
+            title: "Cancel".to_string(),
+            subtitle: None,
+            kind: EntryKind::Cancel,
+        }
+    }
+
+    pub fn with_subtitle(mut self, subtitle: String) -> Self {
+        self.subtitle = Some(subtitle);
+        self
+    }
 }
@@ -0,0 +1,248 @@
+use std::error::Error;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{Atom, AtomEnum, ClientMessageEvent, ConnectionExt, EventMask, Window};
+use x11rb::rust_connection::RustConnection;
+use crate::entry::{OpenWindow, WindowIcon};
+
+type XResult<T> = Result<T, Box<dyn Error>>;
+
+/// Source indication for `_NET_ACTIVE_WINDOW`: a pager or similar tool acting
+/// on the user's behalf, so focus-stealing prevention doesn't apply.
+const SOURCE_PAGER: u32 = 2;
+
+struct Atoms {
+    client_list: Atom,
+    active_window: Atom,
+    wm_name: Atom,
+    wm_pid: Atom,
+    wm_icon: Atom,
+    utf8_string: Atom,
+}
+
+/// A connection to the X server for reading and acting on the window
+/// manager's client list (the EWMH `_NET_*` root and window properties).
+pub struct Ewmh {
+    conn: RustConnection,
+    root: Window,
+    atoms: Atoms,
+}
+
+impl Ewmh {
+    /// Connects to `$DISPLAY`; fails on Wayland sessions without XWayland.
+    pub fn connect() -> XResult<Self> {
+        Self::connect_to(None)
+    }
+
+    /// Connects to `display`, e.g. `:1`; tests pass their own server.
+    fn connect_to(display: Option<&str>) -> XResult<Self> {
+        let (conn, screen) = x11rb::connect(display)?;
+        let root = conn.setup().roots[screen].root;
+        let intern = |name: &[u8]| -> XResult<Atom> { Ok(conn.intern_atom(false, name)?.reply()?.atom) };
+        let atoms = Atoms {
+            client_list: intern(b"_NET_CLIENT_LIST")?,
+            active_window: intern(b"_NET_ACTIVE_WINDOW")?,
+            wm_name: intern(b"_NET_WM_NAME")?,
+            wm_pid: intern(b"_NET_WM_PID")?,
+            wm_icon: intern(b"_NET_WM_ICON")?,
+            utf8_string: intern(b"UTF8_STRING")?,
+        };
+        Ok(Self { conn, root, atoms })
+    }
+
+    /// Managed top-level windows in stacking-independent (mapping) order.
+    /// Icons are only fetched when `icon_size` is given, picking the closest size.
+    pub fn windows(&self, icon_size: Option<u32>) -> XResult<Vec<OpenWindow>> {
+        let ids = self.property32(self.root, self.atoms.client_list, AtomEnum::WINDOW)?;
+        // Windows can close while we read them; skip those rather than fail.
+        Ok(ids.into_iter().filter_map(|id| self.window(id, icon_size).ok()).collect())
+    }
+
+    /// Asks the window manager to raise and focus `id`, switching desktops if needed.
+    pub fn activate(&self, id: u32) -> XResult<()> {
+        let event = ClientMessageEvent::new(
+            32,
+            id,
+            self.atoms.active_window,
+            [SOURCE_PAGER, x11rb::CURRENT_TIME, 0, 0, 0],
+        );
+        self.conn.send_event(
+            false,
+            self.root,
+            EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+            event,
+        )?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    fn window(&self, id: Window, icon_size: Option<u32>) -> XResult<OpenWindow> {
+        let mut title = String::from_utf8_lossy(&self.property8(id, self.atoms.wm_name, self.atoms.utf8_string)?).into_owned();
+        if title.is_empty() {
+            title = String::from_utf8_lossy(&self.property8(id, AtomEnum::WM_NAME, AtomEnum::STRING)?).into_owned();
+        }
+        let (instance, class) = parse_wm_class(&self.property8(id, AtomEnum::WM_CLASS, AtomEnum::STRING)?);
+        let pid = self.property32(id, self.atoms.wm_pid, AtomEnum::CARDINAL)?.first().copied();
+        let icon = match icon_size {
+            Some(size) => pick_icon(&self.property32(id, self.atoms.wm_icon, AtomEnum::CARDINAL)?, size),
+            None => None,
+        };
+        Ok(OpenWindow { id, title, instance, class, pid, icon })
+    }
+
+    fn property8(&self, window: Window, property: impl Into<Atom>, type_: impl Into<Atom>) -> XResult<Vec<u8>> {
+        let reply = self.conn.get_property(false, window, property, type_, 0, u32::MAX)?.reply()?;
+        Ok(reply.value8().map(Iterator::collect).unwrap_or_default())
+    }
+
+    fn property32(&self, window: Window, property: impl Into<Atom>, type_: impl Into<Atom>) -> XResult<Vec<u32>> {
+        let reply = self.conn.get_property(false, window, property, type_, 0, u32::MAX)?.reply()?;
+        Ok(reply.value32().map(Iterator::collect).unwrap_or_default())
+    }
+}
+
+/// `WM_CLASS` holds two NUL-terminated strings: instance, then class.
+fn parse_wm_class(raw: &[u8]) -> (String, String) {
+    let mut parts = raw.split(|&b| b == 0).map(|part| String::from_utf8_lossy(part).into_owned());
+    let instance = parts.next().unwrap_or_default();
+    let class = parts.next().unwrap_or_default();
+    (instance, class)
+}
+
+/// `_NET_WM_ICON` is a sequence of `width, height, ARGB pixels…` images. Picks
+/// the smallest one at least `size` wide, or the largest if all are smaller.
+fn pick_icon(data: &[u32], size: u32) -> Option<WindowIcon> {
+    let mut images: Vec<(u32, u32, &[u32])> = Vec::new();
+    let mut rest = data;
+    while let [width, height, tail @ ..] = rest {
+        let len = (*width as usize).checked_mul(*height as usize)?;
+        if len == 0 || tail.len() < len {
+            break;
+        }
+        images.push((*width, *height, &tail[..len]));
+        rest = &tail[len..];
+    }
+
+    let (width, height, pixels) = images
+        .iter()
+        .filter(|(w, _, _)| *w >= size)
+        .min_by_key(|(w, _, _)| *w)
+        .or_else(|| images.iter().max_by_key(|(w, _, _)| *w))
+        .copied()?;
+
+    let rgba = pixels
+        .iter()
+        .flat_map(|argb| {
+            let [a, r, g, b] = argb.to_be_bytes();
+            [r, g, b, a]
+        })
+        .collect();
+    Some(WindowIcon { width, height, rgba })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+    use x11rb::protocol::xproto::{CreateWindowAux, PropMode, WindowClass};
+    use x11rb::wrapper::ConnectionExt as _;
+
+    /// An `Xvfb` on a display number it picks itself, stopped when dropped.
+    struct Xvfb {
+        server: Child,
+        display: String,
+    }
+
+    impl Xvfb {
+        /// `None` when `Xvfb` isn't installed or fails to start, so the caller can skip.
+        fn start() -> Option<Self> {
+            let server = Command::new("Xvfb")
+                .args(["-displayfd", "1", "-nolisten", "tcp"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .ok()?;
+            let mut xvfb = Self { server, display: String::new() };
+            let mut number = String::new();
+            BufReader::new(xvfb.server.stdout.take()?).read_line(&mut number).ok()?;
+            if number.trim().is_empty() {
+                return None;
+            }
+            xvfb.display = format!(":{}", number.trim());
+            Some(xvfb)
+        }
+    }
+
+    impl Drop for Xvfb {
+        fn drop(&mut self) {
+            let _ = self.server.kill();
+            let _ = self.server.wait();
+        }
+    }
+
+    #[test]
+    fn wm_class_splits_instance_and_class() {
+        assert_eq!(parse_wm_class(b"code\0Code\0"), ("code".to_string(), "Code".to_string()));
+        assert_eq!(parse_wm_class(b""), (String::new(), String::new()));
+    }
+
+    #[test]
+    fn icon_prefers_smallest_size_that_fits() {
+        let mut data = vec![1, 1, 0xff00_0000];
+        data.extend([2, 2]);
+        data.extend([0x80ff_0000; 4]);
+        data.extend([4, 4]);
+        data.extend([0; 16]);
+
+        let icon = pick_icon(&data, 2).unwrap();
+        assert_eq!((icon.width, icon.height), (2, 2));
+        assert_eq!(&icon.rgba[..4], &[0xff, 0x00, 0x00, 0x80]);
+
+        assert_eq!(pick_icon(&data, 64).unwrap().width, 4);
+        // Truncated data yields whatever complete images came before it.
+        assert_eq!(pick_icon(&[8, 8, 0, 0], 8), None);
+    }
+
+    /// Plays the window manager's part by publishing `_NET_CLIENT_LIST`
+    /// itself on a private `Xvfb`, so the desktop's own root window is never
+    /// touched. Skipped when `Xvfb` isn't installed.
+    #[test]
+    fn lists_clients_from_the_root_window() {
+        let Some(xvfb) = Xvfb::start() else { return };
+        let ewmh = Ewmh::connect_to(Some(xvfb.display.as_str())).unwrap();
+        let (conn, screen) = x11rb::connect(Some(xvfb.display.as_str())).unwrap();
+        let root = conn.setup().roots[screen].root;
+
+        let window = conn.generate_id().unwrap();
+        conn.create_window(
+            x11rb::COPY_DEPTH_FROM_PARENT,
+            window,
+            root,
+            0,
+            0,
+            16,
+            16,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            x11rb::COPY_FROM_PARENT,
+            &CreateWindowAux::new(),
+        )
+        .unwrap();
+        conn.change_property8(PropMode::REPLACE, window, ewmh.atoms.wm_name, ewmh.atoms.utf8_string, "Notes — Editor".as_bytes())
+            .unwrap();
+        conn.change_property8(PropMode::REPLACE, window, AtomEnum::WM_CLASS, AtomEnum::STRING, b"editor\0Editor\0")
+            .unwrap();
+        conn.change_property32(PropMode::REPLACE, window, ewmh.atoms.wm_pid, AtomEnum::CARDINAL, &[4242])
+            .unwrap();
+        conn.change_property32(PropMode::REPLACE, root, ewmh.atoms.client_list, AtomEnum::WINDOW, &[window])
+            .unwrap();
+        conn.sync().unwrap();
+
+        let windows = ewmh.windows(None).unwrap();
+        let found = windows.iter().find(|w| w.id == window).unwrap();
+        assert_eq!(found.title, "Notes — Editor");
+        assert_eq!(found.class, "Editor");
+        assert_eq!(found.pid, Some(4242));
+        ewmh.activate(window).unwrap();
+    }
+}
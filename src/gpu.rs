@@ -0,0 +1,154 @@
+use std::fs;
+use std::path::Path;
+
+/// Where the kernel exposes devices; tests point the helpers at a fake tree.
+pub const SYSFS_ROOT: &str = "/sys";
+
+/// A graphics card listed under `class/drm`.
+#[derive(Debug, PartialEq)]
+pub struct Gpu {
+    /// The DRM node name, e.g. `card1`.
+    pub card: String,
+    /// The kernel driver bound to it, e.g. `amdgpu`, `nouveau` or `nvidia`.
+    pub driver: Option<String>,
+    /// Whether the firmware set it up as the boot display.
+    pub boot_vga: bool,
+    /// Its PCI address, e.g. `0000:01:00.0`, when it is a PCI device.
+    pub pci_address: Option<String>,
+}
+
+/// Cards visible under `root`, in node order. Connectors (`card0-eDP-1`) and
+/// render nodes are left out.
+pub fn gpus(root: &Path) -> Vec<Gpu> {
+    let drm = root.join("class/drm");
+    let Ok(read_dir) = fs::read_dir(&drm) else { return Vec::new() };
+    let mut gpus: Vec<Gpu> = read_dir
+        .flatten()
+        .filter_map(|dirent| {
+            let card = dirent.file_name().to_str()?.to_string();
+            let number = card.strip_prefix("card")?;
+            if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let device = drm.join(&card).join("device");
+            let driver = fs::read_link(device.join("driver"))
+                .ok()
+                .and_then(|link| Some(link.file_name()?.to_string_lossy().into_owned()));
+            let boot_vga = fs::read_to_string(device.join("boot_vga")).is_ok_and(|v| v.trim() == "1");
+            let pci_address = fs::read_link(&device)
+                .ok()
+                .and_then(|link| Some(link.file_name()?.to_str()?.to_string()))
+                .filter(|name| is_pci_address(name));
+            Some(Gpu { card, driver, boot_vga, pci_address })
+        })
+        .collect();
+    gpus.sort_by_key(|gpu| gpu.card[4..].parse::<u32>().unwrap_or(u32::MAX));
+    gpus
+}
+
+/// The card to offload rendering to on a hybrid system: the one that isn't
+/// driving the boot display. Machines with a single card have none, and so do
+/// those where no card is marked as the boot display, since any guess could
+/// be the card already in use.
+pub fn discrete(root: &Path) -> Option<Gpu> {
+    let gpus = gpus(root);
+    if gpus.len() < 2 || !gpus.iter().any(|gpu| gpu.boot_vga) {
+        return None;
+    }
+    gpus.into_iter().find(|gpu| !gpu.boot_vga)
+}
+
+/// Whether `name` looks like `domain:bus:slot.function`, e.g. `0000:01:00.0`.
+fn is_pci_address(name: &str) -> bool {
+    let Some((device, function)) = name.rsplit_once('.') else { return false };
+    let parts: Vec<&str> = device.split(':').chain([function]).collect();
+    parts.len() == 4 && parts.iter().all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Environment that makes GL and Vulkan clients render on `gpu`. NVIDIA's
+/// proprietary driver has its own PRIME offload switches; Mesa drivers,
+/// `nouveau` included, use `DRI_PRIME`. It names the card by PCI address,
+/// since `DRI_PRIME=1` only means "not the default" and may pick another card
+/// on machines with more than two.
+pub fn offload_env(gpu: &Gpu) -> Vec<(String, String)> {
+    if gpu.driver.as_deref() == Some("nvidia") {
+        return [
+            ("__NV_PRIME_RENDER_OFFLOAD", "1"),
+            ("__GLX_VENDOR_LIBRARY_NAME", "nvidia"),
+            ("__VK_LAYER_NV_optimus", "NVIDIA_only"),
+        ]
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    }
+    let dri_prime = match &gpu.pci_address {
+        Some(address) => format!("pci-{}", address.replace([':', '.'], "_")),
+        None => "1".to_string(),
+    };
+    vec![("DRI_PRIME".to_string(), dri_prime)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    /// Adds `card` the way sysfs lays it out: `device` links to the PCI
+    /// device at `address`.
+    fn add_card(root: &Path, card: &str, address: &str, driver: &str, boot_vga: &str) {
+        let device = root.join("devices/pci0000:00").join(address);
+        fs::create_dir_all(&device).unwrap();
+        symlink(format!("../../../bus/pci/drivers/{driver}"), device.join("driver")).unwrap();
+        fs::write(device.join("boot_vga"), boot_vga).unwrap();
+        let card_dir = root.join("class/drm").join(card);
+        fs::create_dir_all(&card_dir).unwrap();
+        symlink(format!("../../../devices/pci0000:00/{address}"), card_dir.join("device")).unwrap();
+    }
+
+    #[test]
+    fn finds_the_card_not_driving_the_boot_display() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        add_card(root, "card0", "0000:00:02.0", "i915", "1\n");
+        fs::create_dir_all(root.join("class/drm/card0-eDP-1")).unwrap();
+        fs::create_dir_all(root.join("class/drm/renderD128")).unwrap();
+        assert_eq!(gpus(root).len(), 1);
+        assert_eq!(discrete(root), None);
+
+        add_card(root, "card1", "0000:01:00.0", "nvidia", "0\n");
+        let gpu = discrete(root).unwrap();
+        let expected = Gpu {
+            card: "card1".to_string(),
+            driver: Some("nvidia".to_string()),
+            boot_vga: false,
+            pci_address: Some("0000:01:00.0".to_string()),
+        };
+        assert_eq!(gpu, expected);
+        assert!(offload_env(&gpu).contains(&("__GLX_VENDOR_LIBRARY_NAME".to_string(), "nvidia".to_string())));
+    }
+
+    #[test]
+    fn without_a_boot_display_no_card_is_guessed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        add_card(root, "card0", "0000:00:02.0", "i915", "0\n");
+        add_card(root, "card1", "0000:01:00.0", "amdgpu", "0\n");
+        assert_eq!(gpus(root).len(), 2);
+        assert_eq!(discrete(root), None);
+    }
+
+    #[test]
+    fn mesa_drivers_offload_to_the_card_by_pci_address() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        add_card(root, "card0", "0000:00:02.0", "i915", "1\n");
+        add_card(root, "card1", "0000:03:00.0", "amdgpu", "0\n");
+        add_card(root, "card2", "0000:0a:00.1", "amdgpu", "0\n");
+        assert_eq!(offload_env(&discrete(root).unwrap()), vec![("DRI_PRIME".to_string(), "pci-0000_03_00_0".to_string())]);
+
+        let mut gpu = gpus(root).remove(2);
+        assert_eq!(offload_env(&gpu), vec![("DRI_PRIME".to_string(), "pci-0000_0a_00_1".to_string())]);
+        gpu.pci_address = None;
+        assert_eq!(offload_env(&gpu), vec![("DRI_PRIME".to_string(), "1".to_string())]);
+    }
+}
@@ -0,0 +1,82 @@
+use std::path::Path;
+use std::{fs, io};
+use crate::config::data_path;
+
+/// Commands run from `>` mode.
+pub const COMMANDS: &str = "command_history";
+
+/// Hosts opened from `ssh` mode.
+pub const SSH_HOSTS: &str = "ssh_history";
+
+/// Upper bound on remembered items per history file.
+const MAX_ITEMS: usize = 500;
+
+/// Returns the items recorded under `name`, most recent first. A missing or
+/// unreadable history is treated as empty.
+pub fn load(name: &str) -> Vec<String> {
+    data_path(name).map(|path| load_from(&path)).unwrap_or_default()
+}
+
+/// Moves `item` to the front of the history stored under `name`.
+pub fn record(name: &str, item: &str) -> io::Result<()> {
+    record_to(&data_path(name)?, item, MAX_ITEMS)
+}
+
+/// Items are stored one per line, oldest first, so appending stays cheap to read.
+fn load_from(path: &Path) -> Vec<String> {
+    let Ok(raw) = fs::read_to_string(path) else { return Vec::new() };
+    raw.lines()
+        .rev()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn record_to(path: &Path, item: &str, limit: usize) -> io::Result<()> {
+    let item = item.trim();
+    if item.is_empty() || item.contains('\n') {
+        return Ok(());
+    }
+
+    let mut items = load_from(path);
+    items.retain(|existing| existing != item);
+    items.insert(0, item.to_string());
+    items.truncate(limit);
+
+    let mut out = items.into_iter().rev().collect::<Vec<_>>().join("\n");
+    out.push('\n');
+    fs::write(path, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_moves_item_to_front() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history");
+        record_to(&path, "make", 10).unwrap();
+        record_to(&path, "htop", 10).unwrap();
+        record_to(&path, "make", 10).unwrap();
+
+        assert_eq!(load_from(&path), vec!["make", "htop"]);
+    }
+
+    #[test]
+    fn record_drops_oldest_past_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history");
+        for cmd in ["a", "b", "c"] {
+            record_to(&path, cmd, 2).unwrap();
+        }
+
+        assert_eq!(load_from(&path), vec!["c", "b"]);
+    }
+
+    #[test]
+    fn missing_history_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load_from(&dir.path().join("history")).is_empty());
+    }
+}
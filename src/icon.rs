@@ -1,16 +1,21 @@
+use std::path::Path;
 use std::rc::Rc;
 use gtk::IconLookupFlags;
-use gdk::gdk_pixbuf::Pixbuf;
+use gdk::gdk_pixbuf::{Colorspace, InterpType, Pixbuf};
 use gio::AppInfo;
 use gio::prelude::AppInfoExt;
 use gtk::prelude::{IconThemeExt, ImageExt};
 use crate::config::Config;
+use crate::entry::OpenWindow;
 
 fn get_icon(app: &AppInfo, cfg: &Config) -> Option<Pixbuf> {
-    let icon = app.icon()?;
+    load_gicon(&app.icon()?, cfg)
+}
+
+fn load_gicon(icon: &gio::Icon, cfg: &Config) -> Option<Pixbuf> {
     let icon_theme = gtk::IconTheme::default()?;
     let icon_info = icon_theme.lookup_by_gicon(
-        &icon,
+        icon,
         cfg.icon_size,
         IconLookupFlags::FORCE_SIZE,
     )?;
@@ -39,3 +44,49 @@ pub fn create_generic_icon_widget(icon_name: &str, cfg: Rc<Config>) -> gtk::Imag
     image.set_pixel_size(cfg.icon_size);
     image
 }
+
+/// An icon given by theme name or image path, as script providers do.
+pub fn create_named_icon_widget(icon: &str, fallback: &str, cfg: Rc<Config>) -> gtk::Image {
+    match gio::Icon::for_string(icon).ok().and_then(|gicon| load_gicon(&gicon, &cfg)) {
+        Some(pixbuf) => gtk::Image::from_pixbuf(Some(&pixbuf)),
+        None => create_generic_icon_widget(fallback, cfg),
+    }
+}
+
+pub fn create_file_icon_widget(path: &Path, cfg: Rc<Config>) -> gtk::Image {
+    if path.is_dir() {
+        return create_content_type_icon_widget("inode/directory", cfg);
+    }
+    let (content_type, _) = gio::content_type_guess(Some(path), &[]);
+    create_content_type_icon_widget(&content_type, cfg)
+}
+
+pub fn create_content_type_icon_widget(content_type: &str, cfg: Rc<Config>) -> gtk::Image {
+    match load_gicon(&gio::content_type_get_icon(content_type), &cfg) {
+        Some(pixbuf) => gtk::Image::from_pixbuf(Some(&pixbuf)),
+        None => create_generic_icon_widget("text-x-generic", cfg),
+    }
+}
+
+/// The window's own icon if it set one, else a theme icon named after its
+/// `WM_CLASS`, which matches most applications' icon names.
+pub fn create_window_icon_widget(window: &OpenWindow, cfg: Rc<Config>) -> gtk::Image {
+    if let Some(icon) = &window.icon {
+        let pixbuf = Pixbuf::from_bytes(
+            &glib::Bytes::from(&icon.rgba),
+            Colorspace::Rgb,
+            true,
+            8,
+            icon.width as i32,
+            icon.height as i32,
+            icon.width as i32 * 4,
+        );
+        if let Some(scaled) = pixbuf.scale_simple(cfg.icon_size, cfg.icon_size, InterpType::Bilinear) {
+            return gtk::Image::from_pixbuf(Some(&scaled));
+        }
+    }
+
+    let name = window.instance.to_lowercase();
+    let has_theme_icon = gtk::IconTheme::default().is_some_and(|theme| theme.has_icon(&name));
+    create_generic_icon_widget(if has_theme_icon { &name } else { "window-new" }, cfg)
+}
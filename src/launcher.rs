@@ -1,51 +1,508 @@
-use crate::entry::{SystemAction};
-use gio::prelude::AppInfoExt;
+use std::env;
+use std::ffi::CString;
+use std::fs;
+use std::io::{self, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::DirBuilderExt;
+use std::path::{Path, PathBuf};
+use crate::actions;
+use crate::config::{self, Config};
+use crate::entry::{Entry, EntryKind, Process, RecentDoc, ScriptAction, SystemAction, Unit, UnitAction};
+use crate::gpu;
+use crate::history;
+use crate::providers::files::resolve_path;
+use crate::providers::{processes, units, windows};
+use crate::systemd::{self, Systemd};
+use gio::prelude::{AppInfoExt, AppLaunchContextExt, FileExt};
 use gio::AppInfo;
 use glib::{Cast, SpawnFlags};
 
-pub fn needs_terminal(app: &AppInfo) -> bool {
+const DESKTOP_GROUP: &str = "Desktop Entry";
+
+/// Performs the default action for `entry`.
+pub fn activate(entry: &Entry, cfg: &Config) -> Result<(), String> {
+    match &entry.kind {
+        EntryKind::App(appinfo) => {
+            if cfg.focus_existing_for(appinfo.id().as_deref())
+                && let Some(window) = windows::find_running(appinfo)
+            {
+                return windows::activate(&window);
+            }
+            launch_app(appinfo, cfg)
+        }
+        EntryKind::Action(action) => launch_system_action(action),
+        // Nothing to launch; the result is only shown.
+        EntryKind::Result(_) | EntryKind::Hint => Ok(()),
+        EntryKind::File(path) => open_path(path),
+        EntryKind::Recent(doc) => open_recent(doc, cfg),
+        EntryKind::OpenWith { app, uri } => launch_gui_app(app, &[uri.as_str()], cfg),
+        EntryKind::AppWithArgs { app, args } => launch_with_args(app, args, cfg),
+        EntryKind::Command { command, terminal } => {
+            run_command(command, *terminal, cfg)?;
+            if let Err(e) = history::record(history::COMMANDS, command) {
+                eprintln!("Failed to save command history: {e}");
+            }
+            Ok(())
+        }
+        EntryKind::Executable(path) => spawn_detached(&[path.to_string_lossy().into_owned()])
+            .map_err(|e| format!("Failed to launch '{}': {}", path.display(), e)),
+        EntryKind::Url(url) => open_uri(url),
+        EntryKind::Ssh(host) => {
+            launch_terminal_application(&host.argv(), &terminal_argv(cfg), &LaunchEnv::default())
+                .map_err(|e| format!("Failed to open ssh session to '{}': {}", host, e))?;
+            if let Err(e) = history::record(history::SSH_HOSTS, &host.to_string()) {
+                eprintln!("Failed to save ssh history: {e}");
+            }
+            Ok(())
+        }
+        EntryKind::Emoji { glyph: text, .. } | EntryKind::Clipboard(text) => {
+            copy_to_clipboard(text);
+            Ok(())
+        }
+        EntryKind::Window(window) => windows::activate(window),
+        EntryKind::Process(process) => send_signal(process, libc::SIGTERM),
+        EntryKind::Signal { process, signal } => send_signal(process, *signal),
+        EntryKind::UnitAction { unit, action } => run_unit_action(unit, *action, cfg),
+        EntryKind::Script(item) => run_script_action(&item.action),
+        EntryKind::Plugin(item) => item.action.as_ref().map_or(Ok(()), run_script_action),
+        EntryKind::PanelAction { target, action } => actions::run(target, action, cfg),
+        // Handled by the list itself.
+        EntryKind::Unit(_) | EntryKind::Cancel => Ok(()),
+    }
+}
+
+/// Performs the secondary action for `entry` (Shift+Enter), falling back to
+/// the default action for entries that have none.
+pub fn activate_alternate(entry: &Entry, cfg: &Config) -> Result<(), String> {
+    match &entry.kind {
+        // Whichever of switching and launching Enter doesn't do.
+        EntryKind::App(app) if cfg.focus_existing_for(app.id().as_deref()) => launch_app(app, cfg),
+        EntryKind::App(app) => match windows::find_running(app) {
+            Some(window) => windows::activate(&window),
+            None => launch_app(app, cfg),
+        },
+        EntryKind::Process(process) => send_signal(process, libc::SIGKILL),
+        EntryKind::Unit(unit) => run_unit_action(unit, UnitAction::Logs, cfg),
+        EntryKind::Executable(path) => {
+            launch_terminal_application(&[path.to_string_lossy().into_owned()], &terminal_argv(cfg), &LaunchEnv::default())
+                .map_err(|e| format!("Failed to launch '{}' in terminal: {}", path.display(), e))
+        }
+        _ => activate(entry, cfg),
+    }
+}
+
+pub fn send_signal(process: &Process, signal: i32) -> Result<(), String> {
+    if unsafe { libc::kill(process.pid as libc::pid_t, signal) } == -1 {
+        return Err(format!(
+            "Failed to send {} to '{}' ({}): {}",
+            processes::signal_name(signal),
+            process.name,
+            process.pid,
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}
+
+/// Queues a job for `unit` with the user's service manager, or follows its
+/// journal in the terminal.
+fn run_unit_action(unit: &Unit, action: UnitAction, cfg: &Config) -> Result<(), String> {
+    if action == UnitAction::Logs {
+        return launch_terminal_application(&units::logs_argv(unit), &terminal_argv(cfg), &LaunchEnv::default())
+            .map_err(|e| format!("Failed to show logs of '{}': {}", unit.name, e));
+    }
+    let verb = match action {
+        UnitAction::Start => "start",
+        UnitAction::Stop => "stop",
+        _ => "restart",
+    };
+    Systemd::user()
+        .and_then(|systemd| systemd.run(action, &unit.name))
+        .map_err(|e| format!("Failed to {} '{}': {}", verb, unit.name, e))
+}
+
+/// Runs what a script or plugin row asked for.
+fn run_script_action(action: &ScriptAction) -> Result<(), String> {
+    match action {
+        ScriptAction::Run(argv) => {
+            spawn_detached(argv).map_err(|e| format!("Failed to run '{}': {}", argv.join(" "), e))
+        }
+        ScriptAction::Copy(text) => {
+            copy_to_clipboard(text);
+            Ok(())
+        }
+    }
+}
+
+/// Starts a new instance of `app`, inside the terminal if it asks for one.
+pub fn launch_app(app: &AppInfo, cfg: &Config) -> Result<(), String> {
+    launch_app_in(app, &LaunchEnv::for_app(app, cfg), cfg)
+}
+
+/// Starts `app` rendering on the discrete GPU, whatever its desktop entry prefers.
+pub fn launch_on_discrete_gpu(app: &AppInfo, cfg: &Config) -> Result<(), String> {
+    let gpu = gpu::discrete(Path::new(gpu::SYSFS_ROOT)).ok_or("No discrete GPU found")?;
+    let mut launch_env = LaunchEnv::for_app(app, cfg);
+    launch_env.offload_to(&gpu);
+    launch_app_in(app, &launch_env, cfg)
+}
+
+fn launch_app_in(app: &AppInfo, launch_env: &LaunchEnv, cfg: &Config) -> Result<(), String> {
+    if needs_terminal(app, cfg) {
+        return launch_in_terminal_with(app, &[], launch_env, cfg);
+    }
+    launch_gui_app_in(app, &[], launch_env)
+}
+
+pub fn launch_in_terminal(app: &AppInfo, cfg: &Config) -> Result<(), String> {
+    launch_in_terminal_with(app, &[], &LaunchEnv::for_app(app, cfg), cfg)
+}
+
+/// Runs `app`'s executable in the terminal, followed by its configured `args` and `extra`.
+fn launch_in_terminal_with(app: &AppInfo, extra: &[String], launch_env: &LaunchEnv, cfg: &Config) -> Result<(), String> {
+    let mut argv = vec![app.executable().to_string_lossy().into_owned()];
+    argv.extend_from_slice(&launch_env.args);
+    argv.extend_from_slice(extra);
+    launch_terminal_application(&argv, &terminal_argv(cfg), launch_env)
+        .map_err(|e| format!("Failed to launch terminal app: {}", e))
+}
+
+/// Starts `app` with the files or URLs typed after its name; paths are
+/// resolved from home.
+fn launch_with_args(app: &AppInfo, args: &[String], cfg: &Config) -> Result<(), String> {
+    let is_url = |arg: &String| arg.contains("://");
+    if needs_terminal(app, cfg) {
+        let paths: Vec<String> = args
+            .iter()
+            .map(|arg| if is_url(arg) { arg.clone() } else { resolve_path(arg).to_string_lossy().into_owned() })
+            .collect();
+        return launch_in_terminal_with(app, &paths, &LaunchEnv::for_app(app, cfg), cfg);
+    }
+
+    let uris: Vec<String> = args
+        .iter()
+        .map(|arg| if is_url(arg) { arg.clone() } else { gio::File::for_path(resolve_path(arg)).uri().to_string() })
+        .collect();
+    let uris: Vec<&str> = uris.iter().map(String::as_str).collect();
+    launch_gui_app(app, &uris, cfg)
+}
+
+/// The desktop entry's `Terminal=`, unless `[apps."<id>"]` overrides it.
+pub fn needs_terminal(app: &AppInfo, cfg: &Config) -> bool {
+    if let Some(terminal) = app.id().and_then(|id| cfg.app_settings(&id)?.terminal) {
+        return terminal;
+    }
     if let Some(dai) = app.downcast_ref::<gio::DesktopAppInfo>() {
         return dai.boolean("Terminal");
     }
     false
 }
 
-pub fn launch_gui_app(app: &AppInfo) -> Result<(), String> {
+/// What a launch adds to scout's own environment: the `[apps."<id>"]`
+/// settings, the desktop entry's `Path=` as the default working directory,
+/// and GPU offload variables when it sets `PrefersNonDefaultGPU=true`.
+#[derive(Default)]
+pub struct LaunchEnv {
+    env: Vec<(String, String)>,
+    unset_env: Vec<String>,
+    args: Vec<String>,
+    cwd: Option<PathBuf>,
+    /// The app id to name a transient scope after, when `systemd_scopes` is on.
+    scope: Option<String>,
+}
+
+impl LaunchEnv {
+    pub fn for_app(app: &AppInfo, cfg: &Config) -> Self {
+        Self::for_entry(app.id().as_deref(), app.downcast_ref::<gio::DesktopAppInfo>(), cfg)
+    }
+
+    /// The environment for the desktop entry `dai`, known as `id`.
+    fn for_entry(id: Option<&str>, dai: Option<&gio::DesktopAppInfo>, cfg: &Config) -> Self {
+        let settings = id.and_then(|id| cfg.app_settings(id).cloned()).unwrap_or_default();
+        let path_key = dai
+            .and_then(|dai| dai.string("Path"))
+            .filter(|path| !path.is_empty())
+            .map(|path| PathBuf::from(path.as_str()));
+        let prefers_discrete_gpu = dai.is_some_and(|dai| dai.boolean("PrefersNonDefaultGPU"));
+
+        let mut env: Vec<(String, String)> = settings.env.into_iter().collect();
+        env.sort();
+        let mut launch_env = Self {
+            env,
+            unset_env: settings.unset_env,
+            args: settings.args,
+            cwd: settings.cwd.map(|cwd| resolve_path(&cwd)).or(path_key),
+            scope: id.map(|id| id.trim_end_matches(".desktop").to_string()).filter(|_| cfg.systemd_scopes),
+        };
+        if prefers_discrete_gpu && let Some(gpu) = gpu::discrete(Path::new(gpu::SYSFS_ROOT)) {
+            launch_env.offload_to(&gpu);
+        }
+        launch_env
+    }
+
+    /// Renders on `gpu`. The offload variables go first so an app's own `env`
+    /// settings can still override them.
+    fn offload_to(&mut self, gpu: &gpu::Gpu) {
+        self.env.splice(0..0, gpu::offload_env(gpu));
+    }
+
+    /// Moves the processes just started into their own scope. The app runs
+    /// either way, so a failure is only reported.
+    fn move_to_scope(&self, pids: &[u32]) {
+        let Some(id) = &self.scope else { return };
+        if pids.is_empty() {
+            return;
+        }
+        let name = systemd::app_scope_name(id);
+        if let Err(e) = Systemd::user().and_then(|systemd| systemd.start_scope(&name, pids)) {
+            eprintln!("Failed to start {name}: {e}");
+        }
+    }
+
+    fn apply_to_context(&self, ctx: &gio::AppLaunchContext) {
+        for name in &self.unset_env {
+            ctx.unsetenv(name);
+        }
+        for (name, value) in &self.env {
+            ctx.setenv(name, value);
+        }
+    }
+
+    fn apply_to_launcher(&self, launcher: &gio::SubprocessLauncher) {
+        for name in &self.unset_env {
+            launcher.unsetenv(name);
+        }
+        for (name, value) in &self.env {
+            launcher.setenv(name, value, true);
+        }
+        if let Some(cwd) = &self.cwd {
+            launcher.set_cwd(cwd);
+        }
+    }
+}
+
+pub fn launch_gui_app(app: &AppInfo, uris: &[&str], cfg: &Config) -> Result<(), String> {
+    launch_gui_app_in(app, uris, &LaunchEnv::for_app(app, cfg))
+}
+
+fn launch_gui_app_in(app: &AppInfo, uris: &[&str], launch_env: &LaunchEnv) -> Result<(), String> {
     let ctx = gio::AppLaunchContext::new();
+    launch_env.apply_to_context(&ctx);
 
     // Prefer DesktopAppInfo so we can inject a child-setup hook (setsid).
     if let Some(dai) = app.dynamic_cast_ref::<gio::DesktopAppInfo>() {
         let spawn_flags =
             SpawnFlags::SEARCH_PATH | SpawnFlags::STDOUT_TO_DEV_NULL | SpawnFlags::STDERR_TO_DEV_NULL;
 
+        // GIO only honours `Path=` itself, so a configured `cwd` is entered in the child.
+        let cwd = launch_env.cwd.as_ref().and_then(|cwd| CString::new(cwd.as_os_str().as_bytes()).ok());
+
         // Called after fork() but before exec() in the child.
-        let user_setup: Option<Box<dyn FnOnce()>> = Some(Box::new(|| {
+        let user_setup: Option<Box<dyn FnOnce()>> = Some(Box::new(move || {
             #[cfg(unix)]
             unsafe {
                 let _ = libc::setsid();
+                if let Some(cwd) = &cwd {
+                    let _ = libc::chdir(cwd.as_ptr());
+                }
             }
         }));
 
-        dai.launch_uris_as_manager(&[], Some(&ctx), spawn_flags, user_setup, None)
+        let with_args;
+        let dai = if launch_env.args.is_empty() {
+            dai
+        } else {
+            with_args = with_extra_args(dai, &launch_env.args)
+                .map_err(|e| format!("Failed to launch app '{}': {}", app.name(), e))?;
+            &with_args
+        };
+
+        let mut pids = Vec::new();
+        let mut record_pid = |_: &gio::DesktopAppInfo, pid: glib::Pid| pids.push(pid.0 as u32);
+        let pid_callback: Option<&mut dyn FnMut(&gio::DesktopAppInfo, glib::Pid)> = Some(&mut record_pid);
+        dai.launch_uris_as_manager(uris, Some(&ctx), spawn_flags, user_setup, pid_callback)
             .map_err(|e| format!("Failed to launch app '{}': {}", app.name(), e))?;
+        launch_env.move_to_scope(&pids);
 
         return Ok(());
     }
+
+    // Apps built from a bare command line (e.g. recorded in recent files) have no desktop entry.
+    app.launch_uris(uris, Some(&ctx))
+        .map_err(|e| format!("Failed to launch app '{}': {}", app.name(), e))
+}
+
+/// A copy of `dai` whose `Exec=` passes `args` before the files it is given.
+/// The copy is written to [`launch_copy_path`] and loaded from there, so its
+/// other keys still apply and GIO still tells the app which file started it.
+fn with_extra_args(dai: &gio::DesktopAppInfo, args: &[String]) -> Result<gio::DesktopAppInfo, glib::Error> {
+    let path = dai
+        .filename()
+        .ok_or_else(|| glib::Error::new(gio::IOErrorEnum::NotFound, "no desktop file to add arguments to"))?;
+    let copy = launch_copy_path(&path)
+        .ok_or_else(|| glib::Error::new(gio::IOErrorEnum::NotFound, "no directory for the launch copy"))?;
+    let keyfile = glib::KeyFile::new();
+    keyfile.load_from_file(&path, glib::KeyFileFlags::KEEP_TRANSLATIONS)?;
+    let exec = keyfile.string(DESKTOP_GROUP, "Exec")?;
+    keyfile.set_string(DESKTOP_GROUP, "Exec", &exec_with_args(&exec, args));
+    write_private(&copy, keyfile.to_data().as_bytes())
+        .map_err(|e| glib::Error::new(gio::IOErrorEnum::Failed, &format!("{}: {}", copy.display(), e)))?;
+    gio::DesktopAppInfo::from_filename(&copy).ok_or_else(|| {
+        glib::Error::new(gio::IOErrorEnum::InvalidData, &format!("{} is not a valid application", path.display()))
+    })
+}
+
+/// Where the copy of the desktop file at `path` with extra `args` goes:
+/// `$XDG_RUNTIME_DIR/scout/launch/<file name>`, or under the cache directory
+/// when there is no runtime directory.
+pub fn launch_copy_path(path: &Path) -> Option<PathBuf> {
+    let dir = match env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+        Some(runtime_dir) => PathBuf::from(runtime_dir).join("scout/launch"),
+        None => config::cache_path("launch").ok()?,
+    };
+    Some(dir.join(path.file_name()?))
+}
+
+/// Replaces `path` with an owner-only file holding `data`. It is written
+/// next to `path` first, so a concurrent launch never reads half of it.
+fn write_private(path: &Path, data: &[u8]) -> io::Result<()> {
+    let dir = path.parent().ok_or(io::ErrorKind::NotFound)?;
+    fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(data)?;
+    file.persist(path)?;
     Ok(())
 }
 
-pub fn launch_terminal_application(app_argv: &[String], terminal_argv_prefix: &[String]) -> Result<(), glib::Error> {
+/// `exec` with `args` quoted in just before the argument holding the file or
+/// URL field code, or at the end when there is none.
+fn exec_with_args(exec: &str, args: &[String]) -> String {
+    let quoted: Vec<String> = args.iter().map(|arg| quote_exec_arg(arg)).collect();
+    let quoted = quoted.join(" ");
+    match file_argument_start(exec) {
+        Some(start) => format!("{}{quoted} {}", &exec[..start], &exec[start..]),
+        None => format!("{} {quoted}", exec.trim_end()),
+    }
+}
+
+/// Where the argument holding `%f`, `%F`, `%u` or `%U` starts. The code may be
+/// quoted (`"%u"`) or part of a longer argument (`--open=%f`).
+fn file_argument_start(exec: &str) -> Option<usize> {
+    let mut start = None;
+    let mut quote = None;
+    let mut chars = exec.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if quote.is_none() && (c == ' ' || c == '\t') {
+            start = None;
+            continue;
+        }
+        let argument_start = *start.get_or_insert(i);
+        match c {
+            '"' | '\'' if quote.is_none() => quote = Some(c),
+            c if quote == Some(c) => quote = None,
+            '\\' if quote != Some('\'') => {
+                chars.next();
+            }
+            // `%%` is a literal percent sign.
+            '%' => {
+                if let Some((_, 'f' | 'F' | 'u' | 'U')) = chars.next() {
+                    return Some(argument_start);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Quotes `arg` for `Exec=`: the characters the spec reserves are escaped in
+/// double quotes, and `%` is doubled so it isn't read as a field code.
+fn quote_exec_arg(arg: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in arg.chars() {
+        match c {
+            '"' | '`' | '$' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '%' => quoted.push_str("%%"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Opens `uri` with the user's default handler for its type or scheme.
+pub fn open_uri(uri: &str) -> Result<(), String> {
+    AppInfo::launch_default_for_uri(uri, None::<&gio::AppLaunchContext>)
+        .map_err(|e| format!("Failed to open '{}': {}", uri, e))
+}
+
+pub fn open_path(path: &Path) -> Result<(), String> {
+    open_uri(&gio::File::for_path(path).uri())
+}
+
+/// Reopens a recent document with the application that recorded it, falling
+/// back to the default handler when that application is gone.
+pub fn open_recent(doc: &RecentDoc, cfg: &Config) -> Result<(), String> {
+    match &doc.app {
+        Some(app) => launch_gui_app(app, &[&doc.uri], cfg),
+        None => open_uri(&doc.uri),
+    }
+}
+
+/// Puts `text` on the clipboard and hands it to the clipboard manager, so it
+/// survives scout quitting right after.
+pub fn copy_to_clipboard(text: &str) {
+    let clipboard = gtk::Clipboard::get(&gdk::SELECTION_CLIPBOARD);
+    clipboard.set_text(text);
+    clipboard.store();
+}
+
+/// Runs `command` through `$SHELL -c`, optionally inside the configured terminal.
+pub fn run_command(command: &str, in_terminal: bool, cfg: &Config) -> Result<(), String> {
+    let shell = env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+    let argv = [shell, "-c".to_string(), command.to_string()];
+
+    let result = if in_terminal {
+        launch_terminal_application(&argv, &terminal_argv(cfg), &LaunchEnv::default())
+    } else {
+        spawn_detached(&argv)
+    };
+    result.map_err(|e| format!("Failed to run '{}': {}", command, e))
+}
+
+fn terminal_argv(cfg: &Config) -> Vec<String> {
+    vec![cfg.terminal_emulator.to_string()]
+}
+
+pub fn launch_terminal_application(
+    app_argv: &[String],
+    terminal_argv_prefix: &[String],
+    launch_env: &LaunchEnv,
+) -> Result<(), glib::Error> {
 
     // Build argv = terminal + exec-flag/args + app argv
     let mut argv: Vec<String> = Vec::new();
     argv.extend_from_slice(terminal_argv_prefix);
     argv.extend_from_slice(app_argv);
 
+    spawn(&argv, launch_env)
+}
+
+/// Spawns `argv` in its own session so it outlives scout.
+pub fn spawn_detached(argv: &[String]) -> Result<(), glib::Error> {
+    spawn(argv, &LaunchEnv::default())
+}
+
+fn spawn(argv: &[String], launch_env: &LaunchEnv) -> Result<(), glib::Error> {
     // Convert to &OsStr slices as gtk-rs expects
-    let argv_os: Vec<std::ffi::OsString> = argv.into_iter().map(Into::into).collect();
+    let argv_os: Vec<std::ffi::OsString> = argv.iter().map(Into::into).collect();
     let argv_refs: Vec<&std::ffi::OsStr> = argv_os.iter().map(|s| s.as_os_str()).collect();
 
     let launcher = gio::SubprocessLauncher::new(gio::SubprocessFlags::NONE);
+    launch_env.apply_to_launcher(&launcher);
 
     // setsid() child setup: detach from the launcher's session.
     launcher.set_child_setup(|| {
@@ -57,7 +514,10 @@ pub fn launch_terminal_application(app_argv: &[String], terminal_argv_prefix: &[
 
     // Spawn and immediately drop handle.
     // GSubprocess reaps children quickly to avoid zombies.
-    let _child = launcher.spawn(&argv_refs)?;
+    let child = launcher.spawn(&argv_refs)?;
+    if let Some(pid) = child.identifier().and_then(|pid| pid.parse().ok()) {
+        launch_env.move_to_scope(&[pid]);
+    }
     Ok(())
 }
 
@@ -71,3 +531,91 @@ pub fn launch_system_action(action: &SystemAction) -> Result<(), String> {
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppSettings;
+    use crate::entry::OpenWindow;
+
+    fn desktop_entry(dir: &Path, extra: &str) -> gio::DesktopAppInfo {
+        let path = dir.join("test.desktop");
+        fs::write(&path, format!("[Desktop Entry]\nType=Application\nName=Test\n{extra}")).unwrap();
+        gio::DesktopAppInfo::from_filename(&path).unwrap()
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn extra_args_go_before_the_file_field_code() {
+        assert_eq!(exec_with_args("firefox %u", &args(&["--private-window"])), r#"firefox "--private-window" %u"#);
+        assert_eq!(exec_with_args("gimp-2.10 %U", &args(&["-n", "-s"])), r#"gimp-2.10 "-n" "-s" %U"#);
+        assert_eq!(exec_with_args("code --new-window", &args(&["--verbose"])), r#"code --new-window "--verbose""#);
+        // Quoted codes, codes inside an argument, and a literal `%%f`.
+        assert_eq!(exec_with_args(r#"app "%F" --log"#, &args(&["-x"])), r#"app "-x" "%F" --log"#);
+        assert_eq!(exec_with_args("app --open=%f", &args(&["-x"])), r#"app "-x" --open=%f"#);
+        assert_eq!(exec_with_args(r#"app "a b" 100%%f %u"#, &args(&["-x"])), r#"app "a b" 100%%f "-x" %u"#);
+        assert_eq!(exec_with_args(r#"sh -c "echo \"%%\" $0" %f"#, &args(&["-x"])), r#"sh -c "echo \"%%\" $0" "-x" %f"#);
+    }
+
+    #[test]
+    fn extra_args_are_quoted_for_exec() {
+        let quoted = exec_with_args("app %f", &args(&["a b", r#"say "hi""#, "$HOME", "100%", r"C:\dir"]));
+        assert_eq!(quoted, r#"app "a b" "say \"hi\"" "\$HOME" "100%%" "C:\\dir" %f"#);
+    }
+
+    #[test]
+    fn the_copy_with_extra_args_keeps_the_rest_of_the_entry() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dai = desktop_entry(tmp.path(), "Exec=sh -c true %U\nIcon=test-icon\nStartupWMClass=TestClass\n");
+        let copy = with_extra_args(&dai, &args(&["--new-window"])).unwrap();
+        assert_eq!(copy.commandline(), Some(PathBuf::from(r#"sh -c true "--new-window" %U"#)));
+        assert!(copy.supports_uris());
+        assert_eq!(copy.startup_wm_class().as_deref(), Some("TestClass"));
+        assert_eq!(copy.string("Icon").as_deref(), Some("test-icon"));
+    }
+
+    #[test]
+    fn an_app_started_with_extra_args_is_found_by_its_process() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("scout-test-sleeper.desktop");
+        fs::write(&path, "[Desktop Entry]\nType=Application\nName=Sleeper\nExec=sh -c \"sleep 30\" %U\n").unwrap();
+        let app = gio::DesktopAppInfo::from_filename(&path).unwrap();
+
+        let copy = with_extra_args(&app, &args(&["--extra"])).unwrap();
+        let mut pids = Vec::new();
+        let mut record_pid = |_: &gio::DesktopAppInfo, pid: glib::Pid| pids.push(pid.0 as u32);
+        let pid_callback: Option<&mut dyn FnMut(&gio::DesktopAppInfo, glib::Pid)> = Some(&mut record_pid);
+        copy.launch_uris_as_manager(&[], None::<&gio::AppLaunchContext>, SpawnFlags::SEARCH_PATH, None, pid_callback)
+            .unwrap();
+        let pid = pids[0];
+
+        let window = OpenWindow {
+            id: 1,
+            title: String::new(),
+            instance: String::new(),
+            class: String::new(),
+            pid: Some(pid),
+            icon: None,
+        };
+        let found = windows::find_by_process(app.upcast_ref(), vec![window], Path::new(crate::procfs::PROC_ROOT));
+        unsafe { libc::kill(pid as i32, libc::SIGKILL) };
+        assert_eq!(found.and_then(|window| window.pid), Some(pid));
+    }
+
+    #[test]
+    fn a_configured_cwd_beats_the_desktop_entry_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dai = desktop_entry(tmp.path(), "Exec=sh\nPath=/srv/entry\n");
+        let mut cfg = Config::default();
+        let launch_env = LaunchEnv::for_entry(Some("test.desktop"), Some(&dai), &cfg);
+        assert_eq!(launch_env.cwd, Some(PathBuf::from("/srv/entry")));
+
+        let settings = AppSettings { cwd: Some("/srv/configured".to_string()), ..AppSettings::default() };
+        cfg.apps.insert("test".to_string(), settings);
+        let launch_env = LaunchEnv::for_entry(Some("test.desktop"), Some(&dai), &cfg);
+        assert_eq!(launch_env.cwd, Some(PathBuf::from("/srv/configured")));
+    }
+}
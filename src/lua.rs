@@ -0,0 +1,285 @@
+use std::cell::Cell;
+use std::fs;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use gio::prelude::AppInfoExt;
+use mlua::{Function, HookTriggers, Lua, LuaOptions, LuaSerdeExt, StdLib, Table, Value};
+use crate::config::{self, Config};
+use crate::entry::{Entry, EntryKind};
+use crate::launcher;
+use crate::providers::scripts::{item_entry, Item};
+
+/// Hooks are stopped once they run this long, so a runaway loop can't hang
+/// the window. A query's `on_query` and `score` calls share one budget.
+const TIME_BUDGET: Duration = Duration::from_millis(50);
+
+/// How often, in VM instructions, the time budget is checked.
+const BUDGET_CHECK_INTERVAL: u32 = 1000;
+
+const MEMORY_LIMIT: usize = 16 * 1024 * 1024;
+
+/// Base-library functions that reach the file system, and `load`, which
+/// also accepts precompiled chunks that can break out of the VM.
+const UNSAFE_GLOBALS: &[&str] = &["dofile", "load", "loadfile", "require"];
+
+/// User hooks from `~/.config/scout/init.lua`. The script runs without the
+/// `io`, `os`, `package` and `debug` libraries; instead it gets a `scout`
+/// table with `spawn(argv)`, `copy(text)` and a read-only copy of the config.
+/// Any global function named after a hook is called:
+///
+/// - `on_query(q)` returns extra rows (`{title, subtitle, icon, action, copy}`) for app search
+/// - `score(entry, q, base)` returns an app match's new score
+/// - `on_activate(entry)` returns true when it handled the entry itself
+///
+/// Errors are printed and the hook is skipped.
+#[derive(Default)]
+pub struct LuaHooks {
+    runtime: Option<Runtime>,
+}
+
+struct Runtime {
+    lua: Lua,
+    deadline: Rc<Cell<Option<Instant>>>,
+}
+
+/// The hooks run while answering one query, all against the same deadline.
+pub struct QueryHooks<'a> {
+    runtime: Option<&'a Runtime>,
+    query: &'a str,
+    deadline: Instant,
+}
+
+impl LuaHooks {
+    /// Loads `init.lua` if there is one.
+    pub fn load(cfg: &Config) -> Self {
+        let Ok(path) = config::config_file("init.lua") else { return Self::default() };
+        let Ok(source) = fs::read_to_string(&path) else { return Self::default() };
+        match Runtime::new(&source, &path.display().to_string(), cfg) {
+            Ok(runtime) => Self { runtime: Some(runtime) },
+            Err(e) => {
+                eprintln!("Failed to load {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    /// Starts answering `query`: its time budget runs from now.
+    pub fn for_query<'a>(&'a self, query: &'a str) -> QueryHooks<'a> {
+        QueryHooks { runtime: self.runtime.as_ref(), query, deadline: Instant::now() + TIME_BUDGET }
+    }
+
+    /// True when `on_activate` handled `entry`, so the default action is skipped.
+    pub fn on_activate(&self, entry: &Entry) -> bool {
+        let Some(runtime) = &self.runtime else { return false };
+        runtime
+            .call_hook("on_activate", Instant::now() + TIME_BUDGET, |lua, hook| {
+                let handled: Option<bool> = hook.call(entry_table(lua, entry)?)?;
+                Ok(handled.unwrap_or(false))
+            })
+            .unwrap_or(false)
+    }
+}
+
+impl QueryHooks<'_> {
+    pub fn on_query(&self) -> Vec<Entry> {
+        let Some(runtime) = self.runtime else { return Vec::new() };
+        let result = runtime.call_hook("on_query", self.deadline, |lua, hook| {
+            let items: Option<Value> = hook.call(self.query)?;
+            match items {
+                Some(items) => lua.from_value::<Vec<Item>>(items),
+                None => Ok(Vec::new()),
+            }
+        });
+        result.unwrap_or_default().into_iter().map(item_entry).collect()
+    }
+
+    /// `base` as adjusted by the `score` hook. Once the query's budget is
+    /// spent, the remaining entries keep their base score.
+    pub fn score(&self, entry: &Entry, base: i64) -> i64 {
+        let Some(runtime) = self.runtime else { return base };
+        runtime
+            .call_hook("score", self.deadline, |lua, hook| {
+                let score: Option<f64> = hook.call((entry_table(lua, entry)?, self.query, base))?;
+                Ok(score.map_or(base, |score| score as i64))
+            })
+            .unwrap_or(base)
+    }
+}
+
+impl Runtime {
+    fn new(source: &str, name: &str, cfg: &Config) -> mlua::Result<Self> {
+        let libs = StdLib::TABLE | StdLib::STRING | StdLib::MATH | StdLib::UTF8;
+        let lua = Lua::new_with(libs, LuaOptions::default())?;
+        lua.set_memory_limit(MEMORY_LIMIT)?;
+
+        let deadline: Rc<Cell<Option<Instant>>> = Rc::new(Cell::new(None));
+        let hook_deadline = deadline.clone();
+        lua.set_hook(HookTriggers::new().every_nth_instruction(BUDGET_CHECK_INTERVAL), move |_, _| {
+            match hook_deadline.get() {
+                Some(deadline) if Instant::now() > deadline => {
+                    Err(mlua::Error::RuntimeError("time budget exceeded".to_string()))
+                }
+                _ => Ok(()),
+            }
+        });
+
+        let globals = lua.globals();
+        for name in UNSAFE_GLOBALS {
+            globals.set(*name, Value::Nil)?;
+        }
+        globals.set("scout", api(&lua, cfg)?)?;
+        drop(globals);
+
+        let runtime = Self { lua, deadline };
+        runtime.until(Instant::now() + TIME_BUDGET, |lua| lua.load(source).set_name(name).exec())?;
+        Ok(runtime)
+    }
+
+    /// Runs `call` with the global function `name`, if the script defines one
+    /// and `deadline` hasn't passed.
+    fn call_hook<'lua, T>(
+        &'lua self,
+        name: &str,
+        deadline: Instant,
+        call: impl FnOnce(&'lua Lua, Function<'lua>) -> mlua::Result<T>,
+    ) -> Option<T> {
+        if Instant::now() >= deadline {
+            return None;
+        }
+        let hook: Function = self.lua.globals().get::<_, Option<Function>>(name).ok().flatten()?;
+        match self.until(deadline, |lua| call(lua, hook)) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                eprintln!("Lua {name} failed: {e}");
+                None
+            }
+        }
+    }
+
+    fn until<'lua, T>(&'lua self, deadline: Instant, run: impl FnOnce(&'lua Lua) -> mlua::Result<T>) -> mlua::Result<T> {
+        self.deadline.set(Some(deadline));
+        let result = run(&self.lua);
+        self.deadline.set(None);
+        result
+    }
+}
+
+/// The `scout` table scripts may use.
+fn api<'lua>(lua: &'lua Lua, cfg: &Config) -> mlua::Result<Table<'lua>> {
+    let scout = lua.create_table()?;
+    scout.set("config", lua.to_value(cfg)?)?;
+    scout.set(
+        "spawn",
+        lua.create_function(|_, argv: Vec<String>| {
+            if argv.is_empty() {
+                return Err(mlua::Error::RuntimeError("spawn needs a program to run".to_string()));
+            }
+            launcher::spawn_detached(&argv).map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        })?,
+    )?;
+    scout.set(
+        "copy",
+        lua.create_function(|_, text: String| {
+            launcher::copy_to_clipboard(&text);
+            Ok(())
+        })?,
+    )?;
+    Ok(scout)
+}
+
+/// What hooks see of an entry: `title`, `subtitle`, `kind`, and an `id`
+/// (desktop file id, path, URL or command) where there is one.
+fn entry_table<'lua>(lua: &'lua Lua, entry: &Entry) -> mlua::Result<Table<'lua>> {
+    let (kind, id) = match &entry.kind {
+        EntryKind::App(app) => ("app", app.id().map(|id| id.to_string())),
+        EntryKind::Action(_) => ("action", None),
+        EntryKind::Result(_) => ("result", None),
+        EntryKind::File(path) | EntryKind::Executable(path) => ("file", Some(path.display().to_string())),
+        EntryKind::Url(url) => ("url", Some(url.clone())),
+        EntryKind::Command { command, .. } => ("command", Some(command.clone())),
+        EntryKind::Script(_) => ("script", None),
+        _ => ("other", None),
+    };
+    let table = lua.create_table()?;
+    table.set("title", entry.title.as_str())?;
+    table.set("subtitle", entry.subtitle.as_deref())?;
+    table.set("kind", kind)?;
+    table.set("id", id)?;
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::ScriptAction;
+
+    fn hooks(source: &str) -> LuaHooks {
+        LuaHooks { runtime: Some(Runtime::new(source, "test.lua", &Config::default()).unwrap()) }
+    }
+
+    #[test]
+    fn hooks_add_entries_and_rescore() {
+        let hooks = hooks(
+            r#"
+            function on_query(q)
+                if q == "ip" then
+                    return { { title = "Copy IP", subtitle = "192.168.1.2", copy = "192.168.1.2" } }
+                end
+            end
+            function score(entry, q, base)
+                if entry.title == "Firefox" then return base + scout.config.max_results end
+            end
+            "#,
+        );
+        let entries = hooks.for_query("ip").on_query();
+        assert_eq!(entries.len(), 1);
+        assert!(matches!(&entries[0].kind, EntryKind::Script(item) if item.action == ScriptAction::Copy("192.168.1.2".to_string())));
+        assert!(hooks.for_query("other").on_query().is_empty());
+
+        let firefox = Entry { title: "Firefox".into(), subtitle: None, kind: EntryKind::Result(String::new()) };
+        let files = Entry { title: "Files".into(), ..firefox.clone() };
+        let query = hooks.for_query("f");
+        assert_eq!(query.score(&firefox, 10), 15);
+        assert_eq!(query.score(&files, 10), 10);
+        assert!(!hooks.on_activate(&files));
+    }
+
+    #[test]
+    fn scripts_are_sandboxed_and_time_limited() {
+        let hooks = hooks(
+            r#"
+            function on_activate(entry) return io == nil and os == nil and dofile == nil and load == nil end
+            function score(entry, q, base) while true do end end
+            "#,
+        );
+        let entry = Entry { title: "x".into(), subtitle: None, kind: EntryKind::Result(String::new()) };
+        assert!(hooks.on_activate(&entry));
+        // The endless loop is stopped and the base score kept.
+        assert_eq!(hooks.for_query("x").score(&entry, 7), 7);
+        assert!(Runtime::new("error('broken')", "broken.lua", &Config::default()).is_err());
+    }
+
+    #[test]
+    fn a_query_shares_one_time_budget_across_entries() {
+        let hooks = hooks(
+            r#"
+            function score(entry, q, base)
+                if q == "slow" then while true do end end
+                return base + 1
+            end
+            "#,
+        );
+        let entries: Vec<Entry> = (0..20)
+            .map(|i| Entry { title: format!("app {i}"), subtitle: None, kind: EntryKind::Result(String::new()) })
+            .collect();
+
+        let started = Instant::now();
+        let query = hooks.for_query("slow");
+        assert!(entries.iter().all(|entry| query.score(entry, 3) == 3));
+        // One budget for the whole query, not one per entry.
+        assert!(started.elapsed() < TIME_BUDGET * 3, "took {:?}", started.elapsed());
+
+        // The next query gets a fresh budget.
+        assert_eq!(hooks.for_query("fast").score(&entries[0], 3), 4);
+    }
+}
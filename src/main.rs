@@ -1,10 +1,23 @@
+mod actions;
 mod ui;
 mod search;
 mod launcher;
 mod app;
 mod config;
 mod entry;
+mod ewmh;
 mod icon;
+mod glob;
+mod gpu;
+mod history;
+mod lua;
+mod plugins;
+mod procfs;
+mod providers;
+mod secrets;
+mod systemd;
+#[cfg(test)]
+mod test_bus;
 
 use std::rc::Rc;
 use config::{Config, load_or_create};
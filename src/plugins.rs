@@ -0,0 +1,379 @@
+//! Native plugins: shared libraries in `~/.local/share/scout/plugins` that
+//! add a provider and can draw their own rows. The interface is plain C, so
+//! plugins can be written in any language that can export C symbols.
+//! `include/scout_plugin.h` declares it for C; keep the two in sync.
+//!
+//! A plugin exports two functions:
+//!
+//! ```c
+//! uint32_t scout_plugin_abi_version(void);          // must return SCOUT_ABI_VERSION
+//! const ScoutPlugin *scout_plugin_register(void);   // static, lives as long as the library
+//! ```
+
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::config::{self, Config};
+use crate::entry::{Entry, PluginItem, ScriptAction};
+
+/// Bumped whenever the structs below change incompatibly; plugins built for
+/// another version are not loaded.
+pub const ABI_VERSION: u32 = 1;
+
+const ABI_VERSION_SYMBOL: &CStr = c"scout_plugin_abi_version";
+const REGISTER_SYMBOL: &CStr = c"scout_plugin_register";
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type RegisterFn = unsafe extern "C" fn() -> *const PluginVTable;
+
+/// What a plugin registers (`ScoutPlugin` in C). Strings are UTF-8 and
+/// NUL-terminated; optional members may be NULL.
+#[repr(C)]
+pub struct PluginVTable {
+    /// Repeats the handshake, in case the two exported functions disagree.
+    pub abi_version: u32,
+    pub name: *const c_char,
+    /// Queries starting with this go to `search`, e.g. `"np "`.
+    pub prefix: *const c_char,
+    /// Returns up to `max_results` items for the query with the prefix removed,
+    /// or NULL. The list is handed back to `free_items` once read.
+    pub search: Option<unsafe extern "C" fn(query: *const c_char, max_results: u32) -> *mut ItemList>,
+    pub free_items: Option<unsafe extern "C" fn(items: *mut ItemList)>,
+    /// Fills `container`, a horizontal `GtkBox*`, for an item that set
+    /// `render_data`. Returning false falls back to the standard row.
+    pub render_row: Option<unsafe extern "C" fn(render_data: *const c_char, container: *mut c_void) -> bool>,
+}
+
+/// `ScoutItemList` in C.
+#[repr(C)]
+pub struct ItemList {
+    pub items: *const Item,
+    pub len: usize,
+}
+
+/// `ScoutItem` in C: a result row.
+#[repr(C)]
+pub struct Item {
+    pub title: *const c_char,
+    pub subtitle: *const c_char,
+    /// A theme icon name or an image path.
+    pub icon: *const c_char,
+    /// A NULL-terminated argv spawned on Enter.
+    pub action: *const *const c_char,
+    /// Text copied on Enter when there is no `action`.
+    pub copy: *const c_char,
+    /// Passed back to `render_row` to draw the row.
+    pub render_data: *const c_char,
+}
+
+/// A loaded plugin. Libraries stay loaded for the life of the process.
+struct Plugin {
+    name: String,
+    prefix: Option<String>,
+    vtable: &'static PluginVTable,
+}
+
+/// The plugins that loaded; any that failed the handshake were skipped with
+/// a message rather than taking scout down.
+#[derive(Default)]
+pub struct Plugins {
+    plugins: Vec<Plugin>,
+}
+
+impl Plugins {
+    /// Plugins from scout's data directory.
+    pub fn load_all() -> Self {
+        match config::data_path("plugins") {
+            Ok(dir) => Self::load(&dir),
+            Err(e) => {
+                eprintln!("Failed to find the plugin directory: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Every `*.so` in `dir`, in name order.
+    pub fn load(dir: &Path) -> Self {
+        let Ok(read_dir) = fs::read_dir(dir) else { return Self::default() };
+        let mut paths: Vec<PathBuf> = read_dir
+            .flatten()
+            .map(|dirent| dirent.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "so"))
+            .collect();
+        paths.sort();
+
+        let plugins = paths
+            .iter()
+            .filter_map(|path| match open(path) {
+                Ok(plugin) => Some(plugin),
+                Err(e) => {
+                    eprintln!("Disabling plugin {}: {e}", path.display());
+                    None
+                }
+            })
+            .collect();
+        Self { plugins }
+    }
+
+    pub fn search(&self, query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+        self.plugins.iter().enumerate().find_map(|(index, plugin)| {
+            let pattern = query.strip_prefix(plugin.prefix.as_deref()?)?;
+            Some(plugin.search(index, pattern.trim(), cfg.max_results))
+        })
+    }
+
+    /// Lets the plugin that returned `item` draw it into `container` (a
+    /// `GtkBox*`). False when the standard row should be drawn instead.
+    pub fn render_row(&self, item: &PluginItem, container: *mut c_void) -> bool {
+        let (Some(plugin), Some(data)) = (self.plugins.get(item.plugin), &item.render_data) else { return false };
+        let (Some(render_row), Ok(data)) = (plugin.vtable.render_row, CString::new(data.as_str())) else { return false };
+        unsafe { render_row(data.as_ptr(), container) }
+    }
+}
+
+impl Plugin {
+    fn search(&self, index: usize, pattern: &str, max_results: usize) -> Vec<Entry> {
+        let Some(search) = self.vtable.search else { return Vec::new() };
+        let Ok(query) = CString::new(pattern) else { return Vec::new() };
+        let list = unsafe { search(query.as_ptr(), max_results.try_into().unwrap_or(u32::MAX)) };
+        if list.is_null() {
+            return Vec::new();
+        }
+
+        let entries = unsafe { entries(&*list, index) };
+        match self.vtable.free_items {
+            Some(free_items) => unsafe { free_items(list) },
+            None => eprintln!("Plugin {} leaks its results: no free_items", self.name),
+        }
+        entries.into_iter().take(max_results).collect()
+    }
+}
+
+/// Loads the library at `path` and checks the handshake.
+fn open(path: &Path) -> Result<Plugin, String> {
+    let c_path = CString::new(path.as_os_str().as_encoded_bytes()).map_err(|e| e.to_string())?;
+    let handle = unsafe { libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+    if handle.is_null() {
+        return Err(dl_error());
+    }
+
+    let result = unsafe { register(handle) };
+    if result.is_err() {
+        unsafe { libc::dlclose(handle) };
+    }
+    result
+}
+
+unsafe fn register(handle: *mut c_void) -> Result<Plugin, String> {
+    let abi_version = unsafe { libc::dlsym(handle, ABI_VERSION_SYMBOL.as_ptr()) };
+    if abi_version.is_null() {
+        return Err(format!("not a scout plugin: no {}", ABI_VERSION_SYMBOL.to_string_lossy()));
+    }
+    let abi_version = unsafe { std::mem::transmute::<*mut c_void, AbiVersionFn>(abi_version)() };
+    if abi_version != ABI_VERSION {
+        return Err(format!("built for plugin ABI {abi_version}, scout supports {ABI_VERSION}"));
+    }
+
+    let register = unsafe { libc::dlsym(handle, REGISTER_SYMBOL.as_ptr()) };
+    if register.is_null() {
+        return Err(format!("no {}", REGISTER_SYMBOL.to_string_lossy()));
+    }
+    let vtable = unsafe { std::mem::transmute::<*mut c_void, RegisterFn>(register)() };
+    unsafe { from_vtable(vtable) }
+}
+
+/// Validates what a plugin registered. The vtable must outlive the process,
+/// as libraries are never unloaded once registered.
+unsafe fn from_vtable(vtable: *const PluginVTable) -> Result<Plugin, String> {
+    let Some(vtable) = (unsafe { vtable.as_ref() }) else { return Err("registered nothing".to_string()) };
+    if vtable.abi_version != ABI_VERSION {
+        return Err(format!("registered plugin ABI {}, scout supports {ABI_VERSION}", vtable.abi_version));
+    }
+    let name = unsafe { string(vtable.name) }.ok_or("registered no name")?;
+    let prefix = unsafe { string(vtable.prefix) }.filter(|prefix| !prefix.is_empty());
+    if prefix.is_some() && vtable.search.is_none() {
+        return Err(format!("{name} registered a prefix but no search"));
+    }
+    Ok(Plugin { name, prefix, vtable })
+}
+
+unsafe fn entries(list: &ItemList, plugin: usize) -> Vec<Entry> {
+    if list.items.is_null() {
+        return Vec::new();
+    }
+    let items = unsafe { std::slice::from_raw_parts(list.items, list.len) };
+    items
+        .iter()
+        .filter_map(|item| {
+            let title = unsafe { string(item.title) }?;
+            let action = match unsafe { (argv(item.action), string(item.copy)) } {
+                (argv, _) if !argv.is_empty() => Some(ScriptAction::Run(argv)),
+                (_, Some(text)) => Some(ScriptAction::Copy(text)),
+                _ => None,
+            };
+            let plugin_item = PluginItem {
+                plugin,
+                icon: unsafe { string(item.icon) },
+                action,
+                render_data: unsafe { string(item.render_data) },
+            };
+            let entry = Entry::plugin(plugin_item, title);
+            Some(match unsafe { string(item.subtitle) } {
+                Some(subtitle) => entry.with_subtitle(subtitle),
+                None => entry,
+            })
+        })
+        .collect()
+}
+
+unsafe fn string(ptr: *const c_char) -> Option<String> {
+    (!ptr.is_null()).then(|| unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned())
+}
+
+unsafe fn argv(mut ptr: *const *const c_char) -> Vec<String> {
+    let mut argv = Vec::new();
+    while !ptr.is_null()
+        && let Some(arg) = unsafe { string(*ptr) }
+    {
+        argv.push(arg);
+        ptr = unsafe { ptr.add(1) };
+    }
+    argv
+}
+
+fn dl_error() -> String {
+    let error = unsafe { libc::dlerror() };
+    unsafe { string(error) }.unwrap_or_else(|| "unknown dlopen error".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::mem::offset_of;
+    use std::process::{Command, Stdio};
+    use std::ptr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::entry::EntryKind;
+
+    static FREED: AtomicUsize = AtomicUsize::new(0);
+
+    /// Answers with one item that opens the query and one that copies it.
+    unsafe extern "C" fn search(query: *const c_char, _max_results: u32) -> *mut ItemList {
+        let query = unsafe { CStr::from_ptr(query) }.to_owned();
+        let action = Box::leak(Box::new([c"xdg-open".as_ptr(), query.clone().into_raw().cast_const(), ptr::null()]));
+        let items = Box::leak(Box::new([
+            Item {
+                title: c"Open".as_ptr(),
+                subtitle: query.into_raw(),
+                icon: c"web-browser".as_ptr(),
+                action: action.as_ptr(),
+                copy: ptr::null(),
+                render_data: ptr::null(),
+            },
+            Item {
+                title: c"Copy".as_ptr(),
+                subtitle: ptr::null(),
+                icon: ptr::null(),
+                action: ptr::null(),
+                copy: c"copied".as_ptr(),
+                render_data: c"{\"color\": \"red\"}".as_ptr(),
+            },
+        ]));
+        Box::into_raw(Box::new(ItemList { items: items.as_ptr(), len: items.len() }))
+    }
+
+    unsafe extern "C" fn free_items(items: *mut ItemList) {
+        drop(unsafe { Box::from_raw(items) });
+        FREED.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn vtable(abi_version: u32, with_search: bool) -> *const PluginVTable {
+        Box::leak(Box::new(PluginVTable {
+            abi_version,
+            name: c"echo".as_ptr(),
+            prefix: c"echo ".as_ptr(),
+            search: with_search.then_some(search as _),
+            free_items: Some(free_items),
+            render_row: None,
+        }))
+    }
+
+    #[test]
+    fn handshake_rejects_mismatched_or_incomplete_plugins() {
+        assert!(unsafe { from_vtable(ptr::null()) }.is_err());
+        assert!(matches!(unsafe { from_vtable(vtable(ABI_VERSION + 1, true)) }, Err(e) if e.contains("ABI")));
+        assert!(unsafe { from_vtable(vtable(ABI_VERSION, false)) }.is_err());
+        assert_eq!(unsafe { from_vtable(vtable(ABI_VERSION, true)) }.unwrap().prefix.as_deref(), Some("echo "));
+    }
+
+    #[test]
+    fn plugin_results_become_entries() {
+        let plugins = Plugins { plugins: vec![unsafe { from_vtable(vtable(ABI_VERSION, true)) }.unwrap()] };
+        let entries = plugins.search("echo  https://example.com", &Config::default()).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].subtitle.as_deref(), Some("https://example.com"));
+        assert!(matches!(
+            &entries[0].kind,
+            EntryKind::Plugin(PluginItem { action: Some(ScriptAction::Run(argv)), .. }) if argv == &["xdg-open", "https://example.com"]
+        ));
+        assert!(matches!(
+            &entries[1].kind,
+            EntryKind::Plugin(PluginItem { plugin: 0, action: Some(ScriptAction::Copy(_)), render_data: Some(_), .. })
+        ));
+        assert_eq!(FREED.load(Ordering::SeqCst), 1);
+        assert!(plugins.search("other", &Config::default()).is_none());
+    }
+
+    #[test]
+    fn broken_libraries_are_skipped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        fs::write(dir.join("broken.so"), "not a shared library").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        assert!(Plugins::load(dir).plugins.is_empty());
+    }
+
+    #[test]
+    fn the_c_header_matches_the_structs() {
+        let checks = [
+            ("sizeof(ScoutItem)", size_of::<Item>()),
+            ("offsetof(ScoutItem, subtitle)", offset_of!(Item, subtitle)),
+            ("offsetof(ScoutItem, icon)", offset_of!(Item, icon)),
+            ("offsetof(ScoutItem, action)", offset_of!(Item, action)),
+            ("offsetof(ScoutItem, copy)", offset_of!(Item, copy)),
+            ("offsetof(ScoutItem, render_data)", offset_of!(Item, render_data)),
+            ("sizeof(ScoutItemList)", size_of::<ItemList>()),
+            ("offsetof(ScoutItemList, len)", offset_of!(ItemList, len)),
+            ("sizeof(ScoutPlugin)", size_of::<PluginVTable>()),
+            ("offsetof(ScoutPlugin, name)", offset_of!(PluginVTable, name)),
+            ("offsetof(ScoutPlugin, prefix)", offset_of!(PluginVTable, prefix)),
+            ("offsetof(ScoutPlugin, search)", offset_of!(PluginVTable, search)),
+            ("offsetof(ScoutPlugin, free_items)", offset_of!(PluginVTable, free_items)),
+            ("offsetof(ScoutPlugin, render_row)", offset_of!(PluginVTable, render_row)),
+            ("SCOUT_ABI_VERSION", ABI_VERSION as usize),
+        ];
+        let mut source = String::from("#include <scout_plugin.h>\n");
+        for (expr, value) in checks {
+            source.push_str(&format!("_Static_assert({expr} == {value}, \"{expr}\");\n"));
+        }
+
+        let include = Path::new(env!("CARGO_MANIFEST_DIR")).join("include");
+        let compiler = Command::new("cc")
+            .args(["-std=c11", "-fsyntax-only", "-I"])
+            .arg(&include)
+            .args(["-x", "c", "-"])
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let Ok(mut compiler) = compiler else {
+            eprintln!("No C compiler to check include/scout_plugin.h with");
+            return;
+        };
+        compiler.stdin.take().unwrap().write_all(source.as_bytes()).unwrap();
+        let output = compiler.wait_with_output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+}
@@ -0,0 +1,172 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where the kernel exposes processes; tests point the helpers at a fake tree.
+pub const PROC_ROOT: &str = "/proc";
+
+/// Ids of all processes visible under `root`.
+pub fn pids(root: &Path) -> Vec<u32> {
+    let Ok(read_dir) = fs::read_dir(root) else { return Vec::new() };
+    read_dir
+        .flatten()
+        .filter_map(|dirent| dirent.file_name().to_str()?.parse().ok())
+        .collect()
+}
+
+/// Command-line arguments of `pid`. Kernel threads and processes that exited
+/// meanwhile have none.
+pub fn cmdline(root: &Path, pid: u32) -> Vec<String> {
+    let Ok(raw) = fs::read(root.join(pid.to_string()).join("cmdline")) else { return Vec::new() };
+    raw.split(|&b| b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect()
+}
+
+/// Processes running the program `name`, judged by the file name of their
+/// executable or, when that link isn't readable (other users' processes), of argv[0].
+pub fn pids_running(root: &Path, name: &str) -> Vec<u32> {
+    pids(root)
+        .into_iter()
+        .filter(|&pid| {
+            let exe = fs::read_link(root.join(pid.to_string()).join("exe")).ok();
+            let exe_name = exe.as_deref().and_then(Path::file_name).map(|n| n.to_string_lossy().into_owned());
+            let argv0 = cmdline(root, pid).into_iter().next();
+            let argv0_name = argv0.as_deref().and_then(|arg| Path::new(arg).file_name()).map(|n| n.to_string_lossy().into_owned());
+            exe_name.as_deref() == Some(name) || argv0_name.as_deref() == Some(name)
+        })
+        .collect()
+}
+
+/// The desktop file GIO started `pid` from. Only the process GIO spawned
+/// counts, not children that inherited `GIO_LAUNCHED_DESKTOP_FILE` from it.
+/// Other users' environments aren't readable, so their processes have none.
+pub fn launched_desktop_file(root: &Path, pid: u32) -> Option<PathBuf> {
+    let raw = fs::read(root.join(pid.to_string()).join("environ")).ok()?;
+    let mut file = None;
+    let mut launched_pid = None;
+    for var in raw.split(|&b| b == 0) {
+        let var = String::from_utf8_lossy(var);
+        if let Some(value) = var.strip_prefix("GIO_LAUNCHED_DESKTOP_FILE=") {
+            file = Some(PathBuf::from(value));
+        } else if let Some(value) = var.strip_prefix("GIO_LAUNCHED_DESKTOP_FILE_PID=") {
+            launched_pid = value.parse::<u32>().ok();
+        }
+    }
+    file.filter(|_| launched_pid == Some(pid))
+}
+
+/// The parts of `/proc/<pid>/status` scout shows.
+#[derive(Debug, PartialEq)]
+pub struct Status {
+    pub name: String,
+    pub uid: u32,
+    /// Resident memory; kernel threads have none.
+    pub rss_kib: u64,
+}
+
+pub fn status(root: &Path, pid: u32) -> Option<Status> {
+    parse_status(&fs::read_to_string(root.join(pid.to_string()).join("status")).ok()?)
+}
+
+/// Average CPU use since the process started, as `ps` reports it.
+pub fn cpu_percent(root: &Path, pid: u32, uptime_secs: f64, ticks_per_sec: f64) -> Option<f32> {
+    let stat = fs::read_to_string(root.join(pid.to_string()).join("stat")).ok()?;
+    let (busy_ticks, start_ticks) = parse_stat_times(&stat)?;
+    let elapsed = uptime_secs - start_ticks as f64 / ticks_per_sec;
+    if elapsed <= 0.0 {
+        return Some(0.0);
+    }
+    Some((busy_ticks as f64 / ticks_per_sec / elapsed * 100.0) as f32)
+}
+
+/// Seconds since boot, from `/proc/uptime`.
+pub fn uptime(root: &Path) -> Option<f64> {
+    fs::read_to_string(root.join("uptime")).ok()?.split_whitespace().next()?.parse().ok()
+}
+
+fn parse_status(raw: &str) -> Option<Status> {
+    let mut name = None;
+    let mut uid = None;
+    let mut rss_kib = 0;
+    for line in raw.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        match key {
+            "Name" => name = Some(value.to_string()),
+            // Real, effective, saved and filesystem uids; the real one owns the process.
+            "Uid" => uid = value.split_whitespace().next().and_then(|v| v.parse().ok()),
+            "VmRSS" => rss_kib = value.trim_end_matches("kB").trim().parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+    Some(Status { name: name?, uid: uid?, rss_kib })
+}
+
+/// `utime + stime` and `starttime`, in clock ticks. The command name in
+/// parentheses may itself contain spaces or parentheses, so fields are counted
+/// from the last `)`.
+fn parse_stat_times(raw: &str) -> Option<(u64, u64)> {
+    let fields: Vec<&str> = raw.get(raw.rfind(')')? + 1..)?.split_whitespace().collect();
+    // fields[0] is field 3 (state) in proc(5) numbering.
+    let field = |n: usize| -> Option<u64> { fields.get(n - 3)?.parse().ok() };
+    Some((field(14)? + field(15)?, field(22)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn finds_processes_by_exe_or_argv0() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        for pid in ["10", "11", "12"] {
+            fs::create_dir_all(root.join(pid)).unwrap();
+        }
+        fs::create_dir_all(root.join("self")).unwrap();
+        symlink("/usr/bin/kitty", root.join("10/exe")).unwrap();
+        fs::write(root.join("10/cmdline"), b"kitty\0--single-instance\0").unwrap();
+        fs::write(root.join("11/cmdline"), b"/opt/bin/kitty\0").unwrap();
+        fs::write(root.join("12/cmdline"), b"bash\0").unwrap();
+
+        let mut found = pids_running(root, "kitty");
+        found.sort();
+        assert_eq!(found, vec![10, 11]);
+        assert_eq!(cmdline(root, 10), vec!["kitty", "--single-instance"]);
+    }
+
+    #[test]
+    fn launched_desktop_file_ignores_inherited_variables() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let desktop = "/usr/share/applications/kitty.desktop";
+        for (pid, launched) in [("20", "20"), ("21", "20")] {
+            fs::create_dir_all(root.join(pid)).unwrap();
+            let environ = format!("HOME=/home/me\0GIO_LAUNCHED_DESKTOP_FILE={desktop}\0GIO_LAUNCHED_DESKTOP_FILE_PID={launched}\0");
+            fs::write(root.join(pid).join("environ"), environ).unwrap();
+        }
+
+        assert_eq!(launched_desktop_file(root, 20), Some(PathBuf::from(desktop)));
+        assert_eq!(launched_desktop_file(root, 21), None);
+        assert_eq!(launched_desktop_file(root, 22), None);
+    }
+
+    #[test]
+    fn status_reads_name_uid_and_rss() {
+        let raw = "Name:\tfirefox\nUmask:\t0022\nUid:\t1000\t1000\t1000\t1000\nVmRSS:\t  524288 kB\n";
+        assert_eq!(
+            parse_status(raw),
+            Some(Status { name: "firefox".to_string(), uid: 1000, rss_kib: 524_288 })
+        );
+        // Kernel threads have no VmRSS line.
+        assert_eq!(parse_status("Name:\tkworker/0:1\nUid:\t0\t0\t0\t0\n").unwrap().rss_kib, 0);
+    }
+
+    #[test]
+    fn stat_times_skip_names_with_parentheses() {
+        let raw = "4242 (Web (Content)) S 1 4242 4242 0 -1 4194560 100 0 0 0 250 50 0 0 20 0 30 0 1000 0 0";
+        assert_eq!(parse_stat_times(raw), Some((300, 1000)));
+    }
+}
@@ -0,0 +1,94 @@
+pub mod bookmarks;
+pub mod clipboard;
+pub mod command;
+pub mod emoji;
+mod emoji_data;
+pub mod executables;
+pub mod files;
+pub mod open_with;
+pub mod processes;
+pub mod recent;
+pub mod scripts;
+pub mod ssh;
+pub mod units;
+pub mod web_search;
+pub mod windows;
+
+use std::rc::Rc;
+use crate::config::Config;
+use crate::entry::{Entry, EntryKind};
+use crate::plugins::Plugins;
+
+/// Result sources beyond installed applications. Each provider claims a query
+/// by its prefix and keeps whatever state it needs between keystrokes.
+#[derive(Default)]
+pub struct Providers {
+    bookmarks: bookmarks::Bookmarks,
+    clipboard: Option<Rc<clipboard::ClipboardStore>>,
+    commands: command::CommandMode,
+    files: files::FileIndex,
+    plugins: Option<Rc<Plugins>>,
+    processes: processes::Processes,
+    recent: recent::RecentFiles,
+    scripts: scripts::Scripts,
+    ssh: ssh::SshHosts,
+    units: units::Units,
+    windows: windows::Windows,
+}
+
+impl Providers {
+    /// Providers backed by state that outlives a single window.
+    pub fn new(clipboard: Rc<clipboard::ClipboardStore>, plugins: Rc<Plugins>) -> Self {
+        Self { clipboard: Some(clipboard), plugins: Some(plugins), ..Self::default() }
+    }
+
+    /// Returns `Some` when a provider handles `query`, in which case app search is skipped.
+    pub fn search(&self, query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+        self.scripts
+            .search(query, cfg)
+            .or_else(|| self.plugins.as_ref()?.search(query, cfg))
+            .or_else(|| self.commands.search(query, cfg))
+            .or_else(|| self.clipboard.as_ref()?.search(query, cfg))
+            .or_else(|| self.recent.search(query, cfg))
+            .or_else(|| self.ssh.search(query, cfg))
+            .or_else(|| self.windows.search(query, cfg))
+            .or_else(|| self.processes.search(query, cfg))
+            .or_else(|| self.units.search(query, cfg))
+            .or_else(|| self.bookmarks.search(query, cfg))
+            .or_else(|| emoji::search(query, cfg))
+            .or_else(|| open_with::search(query, cfg))
+            .or_else(|| self.files.search(query, cfg))
+            .or_else(|| web_search::search(query, cfg))
+    }
+
+    /// Sets what to do when a provider that answers asynchronously has new
+    /// results for the current query.
+    pub fn on_update(&self, notify: impl Fn() + 'static) {
+        let notify: Rc<dyn Fn()> = Rc::new(notify);
+        self.files.on_update(notify.clone());
+        self.units.on_update(notify.clone());
+        self.scripts.on_update(move || notify());
+    }
+
+    /// Adds what providers know about app search results, such as which apps are running.
+    pub fn annotate(&self, entries: &mut [Entry], cfg: &Config) {
+        self.windows.annotate(entries, cfg);
+    }
+}
+
+/// Rows that replace the list when `entry` is activated, instead of running it:
+/// skin tones for an emoji, a confirmation before signalling a process, or
+/// what to do with a systemd unit.
+pub fn follow_up(entry: &Entry, alternate: bool) -> Option<Vec<Entry>> {
+    match &entry.kind {
+        EntryKind::Emoji { glyph, skin_tones: true } if alternate => {
+            Some(emoji::skin_tone_variants(glyph, &entry.title))
+        }
+        EntryKind::Process(process) if processes::needs_confirmation(process) => {
+            let signal = if alternate { libc::SIGKILL } else { libc::SIGTERM };
+            Some(processes::confirmation(process, signal))
+        }
+        EntryKind::Unit(unit) if !alternate => Some(units::actions(unit)),
+        _ => None,
+    }
+}
@@ -0,0 +1,261 @@
+use std::cell::OnceCell;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use rusqlite::Connection;
+use serde_json::Value;
+use crate::config::Config;
+use crate::entry::Entry;
+use crate::providers::files::expand_tilde;
+use crate::search::fuzzy_rank;
+
+/// Browser bookmarks, e.g. `bm rust docs`.
+pub const PREFIX: &str = "bm ";
+
+/// Config directories of Chromium-based browsers, relative to home. Each
+/// profile below them (`Default`, `Profile 1`, …) has its own `Bookmarks` file.
+const CHROMIUM_DIRS: &[&str] = &[
+    ".config/chromium",
+    ".config/google-chrome",
+    ".config/google-chrome-beta",
+    ".config/BraveSoftware/Brave-Browser",
+    ".config/microsoft-edge",
+    ".config/vivaldi",
+    "snap/chromium/common/chromium",
+    ".var/app/com.google.Chrome/config/google-chrome",
+    ".var/app/org.chromium.Chromium/config/chromium",
+    ".var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser",
+];
+
+/// Firefox-family profile directories, relative to home. Each profile below
+/// them has its own `places.sqlite`.
+const FIREFOX_DIRS: &[&str] = &[
+    ".mozilla/firefox",
+    ".librewolf",
+    "snap/firefox/common/.mozilla/firefox",
+    ".var/app/org.mozilla.firefox/.mozilla/firefox",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+struct Bookmark {
+    title: String,
+    url: String,
+}
+
+/// Bookmarks from every browser profile found under home, read once per
+/// search session and opened in the default browser.
+#[derive(Default)]
+pub struct Bookmarks {
+    bookmarks: OnceCell<Vec<Bookmark>>,
+}
+
+impl Bookmarks {
+    pub fn search(&self, query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+        let pattern = query.strip_prefix(PREFIX)?.trim();
+        let bookmarks = self.bookmarks.get_or_init(|| load(&expand_tilde("~")));
+
+        let found: Vec<&Bookmark> = if pattern.is_empty() {
+            bookmarks.iter().take(cfg.max_results).collect()
+        } else {
+            let keyed: Vec<(String, &Bookmark)> = bookmarks
+                .iter()
+                .map(|bookmark| (format!("{} {}", bookmark.title, bookmark.url), bookmark))
+                .collect();
+            fuzzy_rank(keyed.iter(), pattern, cfg.max_results, |(key, _)| key)
+                .into_iter()
+                .map(|(_, bookmark)| *bookmark)
+                .collect()
+        };
+        Some(
+            found
+                .into_iter()
+                .map(|bookmark| Entry::url(bookmark.url.clone(), bookmark.title.clone()).with_subtitle(bookmark.url.clone()))
+                .collect(),
+        )
+    }
+}
+
+/// Bookmarks from all profiles, without repeating a URL bookmarked in several.
+fn load(home: &Path) -> Vec<Bookmark> {
+    let mut bookmarks = Vec::new();
+    for path in profile_files(home, CHROMIUM_DIRS, "Bookmarks") {
+        match fs::read_to_string(&path) {
+            Ok(raw) => bookmarks.extend(parse_chromium(&raw)),
+            Err(e) => eprintln!("Failed to read {}: {e}", path.display()),
+        }
+    }
+    for path in profile_files(home, FIREFOX_DIRS, "places.sqlite") {
+        match read_firefox(&path) {
+            Ok(found) => bookmarks.extend(found),
+            Err(e) => eprintln!("Failed to read {}: {e}", path.display()),
+        }
+    }
+
+    let mut seen = HashSet::new();
+    bookmarks.retain(|bookmark| seen.insert(bookmark.url.clone()));
+    bookmarks
+}
+
+/// `file_name` in each profile directory directly below `dirs`.
+fn profile_files(home: &Path, dirs: &[&str], file_name: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = dirs
+        .iter()
+        .filter_map(|dir| fs::read_dir(home.join(dir)).ok())
+        .flat_map(|read_dir| read_dir.flatten().map(|dirent| dirent.path().join(file_name)))
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Walks the `roots` of a Chromium `Bookmarks` file: the bookmark bar, other
+/// and mobile bookmarks, each a tree of folders and URLs.
+fn parse_chromium(raw: &str) -> Vec<Bookmark> {
+    fn walk(node: &Value, out: &mut Vec<Bookmark>) {
+        match node["type"].as_str() {
+            Some("url") => {
+                if let (Some(title), Some(url)) = (node["name"].as_str(), node["url"].as_str()) {
+                    let title = if title.is_empty() { url } else { title };
+                    out.push(Bookmark { title: title.to_string(), url: url.to_string() });
+                }
+            }
+            _ => {
+                for child in node["children"].as_array().into_iter().flatten() {
+                    walk(child, out);
+                }
+            }
+        }
+    }
+
+    let Ok(json) = serde_json::from_str::<Value>(raw) else { return Vec::new() };
+    let mut bookmarks = Vec::new();
+    for root in json["roots"].as_object().into_iter().flat_map(|roots| roots.values()) {
+        walk(root, &mut bookmarks);
+    }
+    bookmarks
+}
+
+/// Firefox keeps `places.sqlite` locked while running, so it is read from a
+/// copy, together with its write-ahead log so recent bookmarks aren't missed.
+/// The copy holds the whole browsing history: it goes to a fresh directory
+/// only the user can enter, in `$XDG_RUNTIME_DIR` when set, and is removed
+/// with that directory.
+fn read_firefox(places: &Path) -> Result<Vec<Bookmark>, String> {
+    let mut builder = tempfile::Builder::new();
+    builder.prefix("scout-places-");
+    let dir = match std::env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+        Some(runtime_dir) => builder.tempdir_in(runtime_dir),
+        None => builder.tempdir(),
+    }
+    .map_err(|e| e.to_string())?;
+
+    let copy = dir.path().join("places.sqlite");
+    let sidecar = |db: &Path, suffix: &str| PathBuf::from(format!("{}-{}", db.display(), suffix));
+    copy_private(places, &copy).map_err(|e| e.to_string())?;
+    let _ = copy_private(&sidecar(places, "wal"), &sidecar(&copy, "wal"));
+    query_firefox(&copy).map_err(|e| e.to_string())
+}
+
+/// Copies `from` into a new owner-only file; anything already at `to`,
+/// a planted symlink included, makes it fail rather than be written through.
+fn copy_private(from: &Path, to: &Path) -> io::Result<()> {
+    let mut source = fs::File::open(from)?;
+    let mut target = fs::OpenOptions::new().write(true).create_new(true).mode(0o600).open(to)?;
+    io::copy(&mut source, &mut target)?;
+    Ok(())
+}
+
+/// Reads the copy, which is ours to write to should SQLite need to replay the log.
+fn query_firefox(db: &Path) -> rusqlite::Result<Vec<Bookmark>> {
+    let conn = Connection::open(db)?;
+    // Type 1 is a bookmark; `place:` URLs are Firefox's saved queries and smart folders.
+    let mut statement = conn.prepare(
+        "SELECT COALESCE(NULLIF(b.title, ''), p.title, p.url), p.url
+         FROM moz_bookmarks b JOIN moz_places p ON p.id = b.fk
+         WHERE b.type = 1 AND p.url NOT LIKE 'place:%'
+         ORDER BY b.lastModified DESC",
+    )?;
+    let rows = statement.query_map([], |row| Ok(Bookmark { title: row.get(0)?, url: row.get(1)? }))?;
+    rows.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chromium_bookmarks_are_read_from_all_roots_and_folders() {
+        let raw = r#"{"roots": {
+            "bookmark_bar": {"type": "folder", "name": "Bar", "children": [
+                {"type": "url", "name": "Rust", "url": "https://www.rust-lang.org/"},
+                {"type": "folder", "name": "Docs", "children": [
+                    {"type": "url", "name": "", "url": "https://docs.rs/"}
+                ]}
+            ]},
+            "other": {"type": "folder", "name": "Other", "children": [
+                {"type": "url", "name": "GTK", "url": "https://gtk.org/"}
+            ]}
+        }, "version": 1}"#;
+        let titles: Vec<String> = parse_chromium(raw).into_iter().map(|b| b.title).collect();
+        assert_eq!(titles, ["Rust", "https://docs.rs/", "GTK"]);
+        assert!(parse_chromium("not json").is_empty());
+    }
+
+    #[test]
+    fn firefox_bookmarks_are_read_from_a_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let places = dir.path().join("places.sqlite");
+        let conn = Connection::open(&places).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+             CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER, title TEXT, lastModified INTEGER);
+             INSERT INTO moz_places VALUES (1, 'https://crates.io/', 'crates.io: Rust Package Registry');
+             INSERT INTO moz_places VALUES (2, 'place:sort=8', NULL);
+             INSERT INTO moz_bookmarks VALUES (1, 2, NULL, 'Toolbar', 0);
+             INSERT INTO moz_bookmarks VALUES (2, 1, 1, NULL, 10);
+             INSERT INTO moz_bookmarks VALUES (3, 1, 2, 'Most Visited', 20);",
+        )
+        .unwrap();
+
+        let bookmarks = read_firefox(&places).unwrap();
+        assert_eq!(
+            bookmarks,
+            [Bookmark { title: "crates.io: Rust Package Registry".to_string(), url: "https://crates.io/".to_string() }]
+        );
+    }
+
+    #[test]
+    fn copies_are_private_and_never_follow_symlinks() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        fs::write(dir.join("places.sqlite"), "history").unwrap();
+        fs::write(dir.join("victim"), "keep").unwrap();
+        symlink(dir.join("victim"), dir.join("planted")).unwrap();
+
+        copy_private(&dir.join("places.sqlite"), &dir.join("copy")).unwrap();
+        assert_eq!(fs::metadata(dir.join("copy")).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(copy_private(&dir.join("places.sqlite"), &dir.join("planted")).is_err());
+        assert_eq!(fs::read_to_string(dir.join("victim")).unwrap(), "keep");
+    }
+
+    #[test]
+    fn profiles_are_found_under_home() {
+        let tmp = tempfile::tempdir().unwrap();
+        let home = tmp.path();
+        for profile in [".config/chromium/Default", ".config/chromium/Profile 1", ".mozilla/firefox/abcd.default-release"] {
+            fs::create_dir_all(home.join(profile)).unwrap();
+        }
+        fs::write(home.join(".config/chromium/Default/Bookmarks"), "{}").unwrap();
+        fs::write(home.join(".mozilla/firefox/abcd.default-release/places.sqlite"), "").unwrap();
+
+        assert_eq!(
+            profile_files(home, CHROMIUM_DIRS, "Bookmarks"),
+            [home.join(".config/chromium/Default/Bookmarks")]
+        );
+        assert_eq!(profile_files(home, FIREFOX_DIRS, "places.sqlite").len(), 1);
+    }
+}
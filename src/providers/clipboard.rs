@@ -0,0 +1,318 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use gtk::prelude::*;
+use crate::config::{config_file, data_path, ClipboardHistory, Config, Persistence};
+use crate::entry::Entry;
+use crate::glob::glob_match;
+use crate::secrets::SecretService;
+use crate::search::fuzzy_rank;
+
+/// Clipboard history, e.g. `cb invoice`.
+pub const PREFIX: &str = "cb ";
+
+const HISTORY_FILE: &str = "clipboard_history";
+const KEY_FILE: &str = "clipboard.key";
+/// What the key is filed under in the Secret Service.
+const KEY_ATTRIBUTES: [(&str, &str); 2] = [("application", "scout"), ("purpose", "clipboard-history")];
+const NONCE_LEN: usize = 12;
+
+/// Rows show this much of a clipping; the label ellipsizes the rest anyway.
+const TITLE_CHARS: usize = 200;
+
+/// Text copied while the daemon runs, most recent first. With
+/// `persist = "encrypted"` the list is sealed with ChaCha20-Poly1305 in the
+/// data directory, and the key is kept elsewhere: in the Secret Service, or
+/// without one in an owner-only file next to `config.toml`. A backup or sync
+/// of the data directory then can't be decrypted on its own. Anything running
+/// as the user can still fetch the key, as it could read the clipboard itself.
+pub struct ClipboardStore {
+    items: RefCell<Vec<String>>,
+    settings: ClipboardHistory,
+    path: Option<PathBuf>,
+    key: RefCell<Option<[u8; 32]>>,
+}
+
+impl ClipboardStore {
+    pub fn load(settings: &ClipboardHistory) -> Self {
+        let store = Self {
+            items: RefCell::new(Vec::new()),
+            settings: settings.clone(),
+            path: data_path(HISTORY_FILE).ok(),
+            key: RefCell::new(None),
+        };
+
+        if let Some(path) = &store.path {
+            match settings.persist {
+                Persistence::Encrypted => match store.key().and_then(|key| read_sealed(path, &key)) {
+                    Ok(found) => *store.items.borrow_mut() = found,
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Err(e) => eprintln!("Failed to read clipboard history: {e}"),
+                },
+                // Leftovers from when persistence was enabled shouldn't linger.
+                Persistence::None => {
+                    let _ = fs::remove_file(path);
+                }
+            }
+        }
+        store
+    }
+
+    pub fn search(&self, query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+        let pattern = query.strip_prefix(PREFIX)?.trim();
+        let items = self.items.borrow();
+
+        let found: Vec<&String> = if pattern.is_empty() {
+            items.iter().take(cfg.max_results).collect()
+        } else {
+            fuzzy_rank(items.iter(), pattern, cfg.max_results, |item| item)
+        };
+        Some(found.into_iter().map(|text| entry(text)).collect())
+    }
+
+    /// Records a copy unless its targets are ignored or it's too large.
+    pub fn record(&self, text: &str, targets: &[String]) {
+        if !self.settings.enabled || self.is_ignored(targets) || !push(&mut self.items.borrow_mut(), text, &self.settings) {
+            return;
+        }
+        if self.settings.persist == Persistence::Encrypted
+            && let Some(path) = &self.path
+            && let Err(e) = self.key().and_then(|key| write_sealed(path, &key, &self.items.borrow()))
+        {
+            eprintln!("Failed to save clipboard history: {e}");
+        }
+    }
+
+    /// The history key, fetched once; after a failure, e.g. while the keyring
+    /// is locked, the next save asks again.
+    fn key(&self) -> io::Result<[u8; 32]> {
+        if let Some(key) = *self.key.borrow() {
+            return Ok(key);
+        }
+        let key = load_key()?;
+        *self.key.borrow_mut() = Some(key);
+        Ok(key)
+    }
+
+    fn is_ignored(&self, targets: &[String]) -> bool {
+        targets
+            .iter()
+            .any(|target| self.settings.ignore_targets.iter().any(|pattern| glob_match(pattern, target)))
+    }
+}
+
+/// Records text put on the clipboard by any application. Targets are fetched
+/// first so copies flagged by a password manager are dropped before their
+/// text is ever read.
+pub fn watch(store: Rc<ClipboardStore>) {
+    let clipboard = gtk::Clipboard::get(&gdk::SELECTION_CLIPBOARD);
+    clipboard.connect_local("owner-change", false, move |_| {
+        let store = store.clone();
+        let clipboard = gtk::Clipboard::get(&gdk::SELECTION_CLIPBOARD);
+        clipboard.request_contents(&gdk::Atom::intern("TARGETS"), move |clipboard, data| {
+            let targets: Vec<String> = data
+                .targets()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|atom| atom.name().to_string())
+                .collect();
+            if store.is_ignored(&targets) {
+                return;
+            }
+            clipboard.request_text(move |_, text| {
+                if let Some(text) = text {
+                    store.record(text, &targets);
+                }
+            });
+        });
+        None
+    });
+}
+
+fn entry(text: &str) -> Entry {
+    let title: String = text.split_whitespace().collect::<Vec<_>>().join(" ").chars().take(TITLE_CHARS).collect();
+    let lines = text.lines().count();
+    let subtitle = if lines > 1 {
+        format!("{} lines, {} characters", lines, text.chars().count())
+    } else {
+        format!("{} characters", text.chars().count())
+    };
+    Entry::clipboard(text.to_string(), title).with_subtitle(subtitle)
+}
+
+/// Moves `text` to the front, trimming to `max_items`. Returns false when the
+/// text isn't worth keeping.
+fn push(items: &mut Vec<String>, text: &str, settings: &ClipboardHistory) -> bool {
+    if text.trim().is_empty() || text.len() > settings.max_bytes {
+        return false;
+    }
+    items.retain(|existing| existing != text);
+    items.insert(0, text.to_string());
+    items.truncate(settings.max_items);
+    true
+}
+
+/// The key sealing the history, created on first use. It goes to the Secret
+/// Service when one runs, else to an owner-only file next to `config.toml`.
+fn load_key() -> io::Result<[u8; 32]> {
+    match SecretService::user().map_err(io::Error::other)? {
+        Some(secrets) => key_from_secret_service(&secrets),
+        None => key_from_file(&config_file(KEY_FILE)?),
+    }
+}
+
+fn key_from_secret_service(secrets: &SecretService) -> io::Result<[u8; 32]> {
+    let attributes = HashMap::from(KEY_ATTRIBUTES);
+    if let Some(key) = secrets.lookup(&attributes).map_err(io::Error::other)? {
+        return to_key(key);
+    }
+    let key = new_key();
+    secrets
+        .store("Scout clipboard history key", &attributes, &key)
+        .map_err(io::Error::other)?;
+    Ok(key)
+}
+
+fn key_from_file(path: &Path) -> io::Result<[u8; 32]> {
+    match fs::read(path) {
+        Ok(bytes) => return to_key(bytes),
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        Err(_) => {}
+    }
+    let key = new_key();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(&key)?;
+    Ok(key)
+}
+
+fn new_key() -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&ChaCha20Poly1305::generate_key(&mut OsRng));
+    key
+}
+
+fn to_key(bytes: Vec<u8>) -> io::Result<[u8; 32]> {
+    bytes
+        .try_into()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "clipboard key has the wrong length"))
+}
+
+/// Clipboard text never contains NUL, so it separates items.
+fn encode(items: &[String]) -> Vec<u8> {
+    items.join("\0").into_bytes()
+}
+
+fn decode(bytes: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(bytes)
+        .split('\0')
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The file holds a fresh nonce followed by the ciphertext.
+fn seal(key: &[u8; 32], plaintext: &[u8]) -> Vec<u8> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, plaintext).expect("encrypting in memory cannot fail");
+    let mut out = nonce.to_vec();
+    out.extend(ciphertext);
+    out
+}
+
+fn unseal(key: &[u8; 32], sealed: &[u8]) -> Option<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    ChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .ok()
+}
+
+fn read_sealed(path: &Path, key: &[u8; 32]) -> io::Result<Vec<String>> {
+    let sealed = fs::read(path)?;
+    let plaintext = unseal(key, &sealed)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "clipboard history failed to decrypt"))?;
+    Ok(decode(&plaintext))
+}
+
+fn write_sealed(path: &Path, key: &[u8; 32], items: &[String]) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(&seal(key, &encode(items)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn push_deduplicates_and_limits() {
+        let settings = ClipboardHistory { max_items: 2, max_bytes: 8, ..ClipboardHistory::default() };
+        let mut items = Vec::new();
+        assert!(push(&mut items, "one", &settings));
+        assert!(push(&mut items, "two", &settings));
+        assert!(push(&mut items, "one", &settings));
+        assert!(push(&mut items, "three", &settings));
+        assert_eq!(items, vec!["three", "one"]);
+
+        assert!(!push(&mut items, "far too long", &settings));
+        assert!(!push(&mut items, "  \n", &settings));
+    }
+
+    #[test]
+    fn password_manager_hint_is_ignored() {
+        let store = ClipboardStore {
+            items: RefCell::new(Vec::new()),
+            settings: ClipboardHistory::default(),
+            path: None,
+            key: RefCell::new(None),
+        };
+        store.record("hunter2", &["UTF8_STRING".to_string(), "x-kde-passwordManagerHint".to_string()]);
+        store.record("hello", &["UTF8_STRING".to_string()]);
+        assert_eq!(*store.items.borrow(), vec!["hello"]);
+    }
+
+    #[test]
+    fn sealed_history_round_trips() {
+        let key = [7u8; 32];
+        let items = vec!["first\nline".to_string(), "second".to_string()];
+        let sealed = seal(&key, &encode(&items));
+        assert!(!sealed.windows(6).any(|w| w == b"second"));
+        assert_eq!(unseal(&key, &sealed).map(|p| decode(&p)), Some(items));
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(unseal(&key, &tampered), None);
+        assert_eq!(unseal(&[8u8; 32], &sealed), None);
+    }
+
+    #[test]
+    fn key_file_is_private_and_reused() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("config/clipboard.key");
+
+        let key = key_from_file(&path).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(key_from_file(&path).unwrap(), key);
+    }
+}
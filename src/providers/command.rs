@@ -0,0 +1,114 @@
+use std::cell::OnceCell;
+use crate::config::Config;
+use crate::entry::Entry;
+use crate::history;
+use crate::providers::executables::scan_path;
+use crate::search::fuzzy_rank;
+
+/// `>cmd` runs through `$SHELL -c`; `>>cmd` runs inside the configured terminal.
+pub const PREFIX: &str = ">";
+pub const TERMINAL_PREFIX: &str = ">>";
+
+/// Command mode: the typed command first, then matching history, then `$PATH`
+/// completions while the first word is still being typed.
+#[derive(Default)]
+pub struct CommandMode {
+    history: OnceCell<Vec<String>>,
+    executables: OnceCell<Vec<String>>,
+}
+
+impl CommandMode {
+    pub fn search(&self, query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+        let (command, terminal) = match query.strip_prefix(TERMINAL_PREFIX) {
+            Some(rest) => (rest, true),
+            None => (query.strip_prefix(PREFIX)?, false),
+        };
+        let command = command.trim();
+
+        let mut entries: Vec<Entry> = Vec::new();
+        if !command.is_empty() {
+            let how = if terminal { "Run in terminal" } else { "Run with $SHELL" };
+            entries.push(Entry::command(command.to_string(), terminal).with_subtitle(how.to_string()));
+        }
+
+        let history = self.history.get_or_init(|| history::load(history::COMMANDS));
+        let previous: Vec<&String> = if command.is_empty() {
+            history.iter().take(cfg.max_results).collect()
+        } else {
+            fuzzy_rank(history.iter(), command, cfg.max_results, |c| c)
+        };
+        for cmd in previous {
+            entries.push(Entry::command(cmd.clone(), terminal).with_subtitle("From history".to_string()));
+        }
+
+        if !command.is_empty() && !command.contains(char::is_whitespace) {
+            let executables = self.executables.get_or_init(|| {
+                scan_path()
+                    .iter()
+                    .filter_map(|path| path.file_name())
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .collect()
+            });
+            for name in completions(executables, command, cfg.max_results) {
+                entries.push(Entry::command(name.clone(), terminal).with_subtitle("On $PATH".to_string()));
+            }
+        }
+
+        dedup_by_title(&mut entries);
+        entries.truncate(cfg.max_results);
+        Some(entries)
+    }
+}
+
+/// Executables starting with `word`, shortest first.
+fn completions<'a>(executables: &'a [String], word: &str, limit: usize) -> Vec<&'a String> {
+    let mut matches: Vec<&String> = executables.iter().filter(|name| name.starts_with(word)).collect();
+    matches.sort_by_key(|name| name.len());
+    matches.truncate(limit);
+    matches
+}
+
+/// Keeps the first entry for each command so the typed row wins over history.
+fn dedup_by_title(entries: &mut Vec<Entry>) {
+    let mut seen = std::collections::HashSet::new();
+    entries.retain(|entry| seen.insert(entry.title.clone()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completions_prefer_shorter_names() {
+        let executables: Vec<String> = ["makepkg", "make", "xmake", "makeinfo"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let found = completions(&executables, "make", 2);
+        assert_eq!(found, vec!["make", "makepkg"]);
+    }
+
+    #[test]
+    fn typed_command_comes_first() {
+        let mode = CommandMode::default();
+        let _ = mode.history.set(vec!["make -C ~/proj".to_string(), "htop".to_string()]);
+        let _ = mode.executables.set(Vec::new());
+        let cfg = Config::default();
+
+        let results = mode.search(">make", &cfg).unwrap();
+        assert_eq!(results[0].title, "make");
+        assert_eq!(results[1].title, "make -C ~/proj");
+    }
+
+    #[test]
+    fn double_prefix_runs_in_terminal() {
+        let mode = CommandMode::default();
+        let _ = mode.history.set(Vec::new());
+        let _ = mode.executables.set(Vec::new());
+        let results = mode.search(">>htop", &Config::default()).unwrap();
+        assert!(matches!(
+            results[0].kind,
+            crate::entry::EntryKind::Command { terminal: true, .. }
+        ));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::config::Config;
+use crate::entry::Entry;
+use crate::providers::emoji_data::{Emoji, EMOJI};
+use crate::search::fuzzy_scores;
+
+/// Emoji and symbols by name or keyword, e.g. `:thumbs` or `:U+2603`.
+pub const PREFIX: &str = ":";
+
+/// Added when every query word starts a keyword or name word, so `:lol`
+/// finds 😂 ahead of loose fuzzy matches on names.
+const KEYWORD_BONUS: i64 = 1000;
+
+/// Fitzpatrick modifiers with their CLDR names, lightest first.
+const SKIN_TONES: [(char, &str); 5] = [
+    ('\u{1f3fb}', "light skin tone"),
+    ('\u{1f3fc}', "medium-light skin tone"),
+    ('\u{1f3fd}', "medium skin tone"),
+    ('\u{1f3fe}', "medium-dark skin tone"),
+    ('\u{1f3ff}', "dark skin tone"),
+];
+
+pub fn search(query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+    let pattern = query.strip_prefix(PREFIX)?.trim().to_lowercase();
+
+    if let Some(entry) = code_point(&pattern) {
+        return Some(vec![entry]);
+    }
+    if pattern.is_empty() {
+        return Some(EMOJI.iter().take(cfg.max_results).map(entry).collect());
+    }
+
+    let mut scored = fuzzy_scores(EMOJI.iter(), &pattern, |emoji| emoji.name);
+    for (score, emoji) in scored.iter_mut() {
+        if keywords_match(emoji, &pattern) {
+            *score += KEYWORD_BONUS;
+        }
+    }
+    let keyword_only: Vec<(i64, &Emoji)> = EMOJI
+        .iter()
+        .filter(|emoji| keywords_match(emoji, &pattern) && !scored.iter().any(|(_, e)| e.glyph == emoji.glyph))
+        .map(|emoji| (KEYWORD_BONUS, emoji))
+        .collect();
+    scored.extend(keyword_only);
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+    Some(scored.into_iter().take(cfg.max_results).map(|(_, emoji)| entry(emoji)).collect())
+}
+
+/// The default-toned glyph followed by one row per skin tone.
+pub fn skin_tone_variants(glyph: &str, name: &str) -> Vec<Entry> {
+    let mut variants = vec![Entry::emoji(glyph.to_string(), name.to_string(), false)];
+    for (tone, tone_name) in SKIN_TONES {
+        let toned = with_skin_tone(glyph, tone);
+        let subtitle = code_points(&toned);
+        variants.push(Entry::emoji(toned, format!("{name}: {tone_name}"), false).with_subtitle(subtitle));
+    }
+    variants
+}
+
+fn entry(emoji: &Emoji) -> Entry {
+    let mut subtitle = code_points(emoji.glyph);
+    if emoji.skin_tones {
+        subtitle.push_str(" · Shift+Enter for skin tones");
+    }
+    Entry::emoji(emoji.glyph.to_string(), emoji.name.to_string(), emoji.skin_tones).with_subtitle(subtitle)
+}
+
+/// Every query word must start one of the emoji's keywords or name words.
+fn keywords_match(emoji: &Emoji, pattern: &str) -> bool {
+    let words: Vec<String> = emoji
+        .keywords
+        .split_whitespace()
+        .chain(emoji.name.split_whitespace())
+        .map(str::to_lowercase)
+        .collect();
+    pattern
+        .split_whitespace()
+        .all(|part| words.iter().any(|word| word.starts_with(part)))
+}
+
+/// `U+2603` or `u+1f600` inserts that character directly, named or not.
+fn code_point(pattern: &str) -> Option<Entry> {
+    let hex = pattern.strip_prefix("u+")?;
+    let c = char::from_u32(u32::from_str_radix(hex, 16).ok()?)?;
+    let known = EMOJI.iter().find(|emoji| emoji.glyph.chars().eq([c]));
+    let name = known.map_or_else(|| format!("U+{:04X}", c as u32), |emoji| emoji.name.to_string());
+    Some(Entry::emoji(c.to_string(), name, false).with_subtitle(code_points(&c.to_string())))
+}
+
+/// The modifier follows the base character, replacing any emoji presentation
+/// selector, so it also lands correctly inside ZWJ sequences like 🧑‍💻.
+fn with_skin_tone(glyph: &str, tone: char) -> String {
+    let mut chars = glyph.chars();
+    let mut out = String::new();
+    if let Some(base) = chars.next() {
+        out.push(base);
+        out.push(tone);
+    }
+    let rest = chars.as_str();
+    out.push_str(rest.strip_prefix('\u{fe0f}').unwrap_or(rest));
+    out
+}
+
+fn code_points(glyph: &str) -> String {
+    glyph
+        .chars()
+        .map(|c| format!("U+{:04X}", c as u32))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::EntryKind;
+
+    fn glyphs(results: &[Entry]) -> Vec<String> {
+        results
+            .iter()
+            .filter_map(|entry| match &entry.kind {
+                EntryKind::Emoji { glyph, .. } => Some(glyph.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_by_name_and_keyword() {
+        let cfg = Config::default();
+        assert_eq!(glyphs(&search(":thumbs up", &cfg).unwrap())[0], "👍");
+        assert!(glyphs(&search(":lol", &cfg).unwrap()).contains(&"😂".to_string()));
+        assert!(search("thumbs", &cfg).is_none());
+    }
+
+    #[test]
+    fn code_point_query_inserts_character() {
+        let results = search(":U+2603", &Config::default()).unwrap();
+        assert_eq!(glyphs(&results), vec!["\u{2603}"]);
+        assert_eq!(results[0].title, "U+2603");
+
+        let named = search(":u+1f680", &Config::default()).unwrap();
+        assert_eq!(named[0].title, "rocket");
+    }
+
+    #[test]
+    fn skin_tone_replaces_presentation_selector() {
+        assert_eq!(with_skin_tone("👍", '\u{1f3fd}'), "👍\u{1f3fd}");
+        assert_eq!(with_skin_tone("✌️", '\u{1f3fb}'), "✌\u{1f3fb}");
+        assert_eq!(with_skin_tone("🧑‍💻", '\u{1f3ff}'), "🧑\u{1f3ff}\u{200d}💻");
+    }
+
+    #[test]
+    fn variants_list_every_tone() {
+        let variants = skin_tone_variants("👋", "waving hand");
+        assert_eq!(variants.len(), 6);
+        assert_eq!(variants[3].title, "waving hand: medium skin tone");
+    }
+}
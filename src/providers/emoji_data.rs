@@ -0,0 +1,434 @@
+//! Bundled emoji and symbol names, following CLDR short names and keywords.
+
+pub struct Emoji {
+    pub glyph: &'static str,
+    pub name: &'static str,
+    pub keywords: &'static str,
+    /// Accepts a Fitzpatrick skin-tone modifier.
+    pub skin_tones: bool,
+}
+
+const fn e(glyph: &'static str, name: &'static str, keywords: &'static str) -> Emoji {
+    Emoji { glyph, name, keywords, skin_tones: false }
+}
+
+const fn t(glyph: &'static str, name: &'static str, keywords: &'static str) -> Emoji {
+    Emoji { glyph, name, keywords, skin_tones: true }
+}
+
+pub static EMOJI: &[Emoji] = &[
+    // Smileys & emotion
+    e("😀", "grinning face", "smile happy joy"),
+    e("😃", "grinning face with big eyes", "smile happy joy open"),
+    e("😄", "grinning face with smiling eyes", "smile happy joy laugh"),
+    e("😁", "beaming face with smiling eyes", "grin happy teeth"),
+    e("😆", "grinning squinting face", "laugh happy satisfied"),
+    e("😅", "grinning face with sweat", "relief nervous laugh"),
+    e("🤣", "rolling on the floor laughing", "rofl lol laugh"),
+    e("😂", "face with tears of joy", "lol laugh cry tears"),
+    e("🙂", "slightly smiling face", "smile"),
+    e("🙃", "upside-down face", "sarcasm silly"),
+    e("😉", "winking face", "wink flirt"),
+    e("😊", "smiling face with smiling eyes", "blush happy"),
+    e("😇", "smiling face with halo", "angel innocent"),
+    e("🥰", "smiling face with hearts", "love adore crush"),
+    e("😍", "smiling face with heart-eyes", "love crush"),
+    e("🤩", "star-struck", "excited stars wow"),
+    e("😘", "face blowing a kiss", "kiss love"),
+    e("😋", "face savoring food", "yum delicious tasty"),
+    e("😛", "face with tongue", "tongue playful"),
+    e("😜", "winking face with tongue", "joke crazy playful"),
+    e("🤪", "zany face", "crazy goofy wild"),
+    e("🤑", "money-mouth face", "rich money dollar"),
+    e("🤗", "smiling face with open hands", "hug hugging"),
+    e("🤭", "face with hand over mouth", "oops giggle"),
+    e("🤫", "shushing face", "quiet shh silence"),
+    e("🤔", "thinking face", "think hmm ponder"),
+    e("🤐", "zipper-mouth face", "secret quiet"),
+    e("🤨", "face with raised eyebrow", "skeptic doubt suspicious"),
+    e("😐", "neutral face", "meh blank"),
+    e("😑", "expressionless face", "blank unamused"),
+    e("😶", "face without mouth", "silent speechless"),
+    e("😏", "smirking face", "smirk smug"),
+    e("😒", "unamused face", "meh unhappy"),
+    e("🙄", "face with rolling eyes", "eyeroll whatever"),
+    e("😬", "grimacing face", "grimace awkward"),
+    e("🤥", "lying face", "lie pinocchio"),
+    e("😌", "relieved face", "relief calm"),
+    e("😔", "pensive face", "sad dejected"),
+    e("😪", "sleepy face", "tired sleep"),
+    e("🤤", "drooling face", "drool hungry"),
+    e("😴", "sleeping face", "sleep zzz tired"),
+    e("😷", "face with medical mask", "sick mask ill"),
+    e("🤒", "face with thermometer", "sick fever ill"),
+    e("🤕", "face with head-bandage", "hurt injury"),
+    e("🤢", "nauseated face", "sick vomit gross"),
+    e("🤮", "face vomiting", "sick puke"),
+    e("🤧", "sneezing face", "sneeze cold"),
+    e("🥵", "hot face", "hot heat sweat"),
+    e("🥶", "cold face", "cold freezing ice"),
+    e("🥴", "woozy face", "dizzy drunk"),
+    e("😵", "face with crossed-out eyes", "dizzy dead"),
+    e("🤯", "exploding head", "mind blown shocked"),
+    e("🤠", "cowboy hat face", "cowboy"),
+    e("🥳", "partying face", "party celebrate birthday"),
+    e("😎", "smiling face with sunglasses", "cool sunglasses"),
+    e("🤓", "nerd face", "nerd geek"),
+    e("🧐", "face with monocle", "inspect fancy"),
+    e("😕", "confused face", "confused puzzled"),
+    e("😟", "worried face", "worried concern"),
+    e("🙁", "slightly frowning face", "frown sad"),
+    e("😮", "face with open mouth", "surprise wow"),
+    e("😲", "astonished face", "shocked surprised"),
+    e("😳", "flushed face", "embarrassed blush"),
+    e("🥺", "pleading face", "puppy eyes please"),
+    e("😦", "frowning face with open mouth", "frown"),
+    e("😨", "fearful face", "fear scared"),
+    e("😰", "anxious face with sweat", "nervous anxious"),
+    e("😢", "crying face", "cry sad tear"),
+    e("😭", "loudly crying face", "sob cry sad"),
+    e("😱", "face screaming in fear", "scream scared"),
+    e("😖", "confounded face", "confounded"),
+    e("😞", "disappointed face", "disappointed sad"),
+    e("😓", "downcast face with sweat", "sweat hard work"),
+    e("😩", "weary face", "tired weary"),
+    e("😫", "tired face", "tired exhausted"),
+    e("🥱", "yawning face", "yawn bored tired"),
+    e("😤", "face with steam from nose", "triumph frustrated"),
+    e("😡", "enraged face", "angry mad rage"),
+    e("😠", "angry face", "angry mad"),
+    e("🤬", "face with symbols on mouth", "swear curse"),
+    e("😈", "smiling face with horns", "devil evil"),
+    e("💀", "skull", "dead death"),
+    e("💩", "pile of poo", "poop"),
+    e("🤡", "clown face", "clown"),
+    e("👻", "ghost", "boo halloween"),
+    e("👽", "alien", "ufo space"),
+    e("🤖", "robot", "bot machine"),
+    e("😺", "grinning cat", "cat smile"),
+    e("🙈", "see-no-evil monkey", "monkey see no evil"),
+    e("🙉", "hear-no-evil monkey", "monkey hear no evil"),
+    e("🙊", "speak-no-evil monkey", "monkey speak no evil"),
+    // Hearts & symbols of emotion
+    e("❤️", "red heart", "love heart"),
+    e("🧡", "orange heart", "love heart"),
+    e("💛", "yellow heart", "love heart"),
+    e("💚", "green heart", "love heart"),
+    e("💙", "blue heart", "love heart"),
+    e("💜", "purple heart", "love heart"),
+    e("🖤", "black heart", "love heart"),
+    e("🤍", "white heart", "love heart"),
+    e("💔", "broken heart", "heartbreak sad"),
+    e("💯", "hundred points", "100 perfect score"),
+    e("💢", "anger symbol", "angry"),
+    e("💥", "collision", "boom explosion"),
+    e("💫", "dizzy", "star"),
+    e("💦", "sweat droplets", "water splash"),
+    e("💬", "speech balloon", "chat comment"),
+    e("💤", "zzz", "sleep"),
+    // People & body
+    t("👋", "waving hand", "wave hello bye"),
+    t("🤚", "raised back of hand", "hand"),
+    t("✋", "raised hand", "high five stop"),
+    t("🖖", "vulcan salute", "spock star trek"),
+    t("👌", "OK hand", "ok perfect"),
+    t("🤌", "pinched fingers", "italian"),
+    t("🤏", "pinching hand", "small tiny"),
+    t("✌️", "victory hand", "peace victory"),
+    t("🤞", "crossed fingers", "luck hope"),
+    t("🤟", "love-you gesture", "love you"),
+    t("🤘", "sign of the horns", "rock metal"),
+    t("🤙", "call me hand", "call shaka"),
+    t("👈", "backhand index pointing left", "point left"),
+    t("👉", "backhand index pointing right", "point right"),
+    t("👆", "backhand index pointing up", "point up"),
+    t("👇", "backhand index pointing down", "point down"),
+    t("☝️", "index pointing up", "point up"),
+    t("👍", "thumbs up", "+1 like yes approve"),
+    t("👎", "thumbs down", "-1 dislike no"),
+    t("✊", "raised fist", "fist power"),
+    t("👊", "oncoming fist", "punch fist bump"),
+    t("👏", "clapping hands", "clap applause"),
+    t("🙌", "raising hands", "hooray celebrate"),
+    t("👐", "open hands", "hands"),
+    t("🤲", "palms up together", "prayer"),
+    e("🤝", "handshake", "deal agreement"),
+    t("🙏", "folded hands", "please pray thanks"),
+    t("✍️", "writing hand", "write"),
+    t("💪", "flexed biceps", "strong muscle"),
+    e("🧠", "brain", "smart think"),
+    e("👀", "eyes", "look see watch"),
+    t("👶", "baby", "child infant"),
+    t("🧑", "person", "adult"),
+    t("👩", "woman", "adult female"),
+    t("👨", "man", "adult male"),
+    t("🧑‍💻", "technologist", "developer coder programmer computer"),
+    t("🤷", "person shrugging", "shrug idk whatever"),
+    t("🤦", "person facepalming", "facepalm disbelief"),
+    t("🙋", "person raising hand", "question volunteer"),
+    t("🏃", "person running", "run jog"),
+    // Animals & nature
+    e("🐶", "dog face", "dog puppy pet"),
+    e("🐱", "cat face", "cat kitten pet"),
+    e("🐭", "mouse face", "mouse"),
+    e("🐰", "rabbit face", "bunny rabbit"),
+    e("🦊", "fox", "fox"),
+    e("🐻", "bear", "bear"),
+    e("🐼", "panda", "panda"),
+    e("🐨", "koala", "koala"),
+    e("🐯", "tiger face", "tiger"),
+    e("🦁", "lion", "lion"),
+    e("🐮", "cow face", "cow"),
+    e("🐷", "pig face", "pig"),
+    e("🐸", "frog", "frog"),
+    e("🐵", "monkey face", "monkey"),
+    e("🐔", "chicken", "chicken"),
+    e("🐧", "penguin", "penguin linux tux"),
+    e("🐦", "bird", "bird"),
+    e("🦆", "duck", "duck"),
+    e("🦉", "owl", "owl"),
+    e("🐝", "honeybee", "bee"),
+    e("🐛", "bug", "bug insect"),
+    e("🦋", "butterfly", "butterfly"),
+    e("🐌", "snail", "slow snail"),
+    e("🐢", "turtle", "slow turtle"),
+    e("🐍", "snake", "python snake"),
+    e("🦀", "crab", "crab rust ferris"),
+    e("🐙", "octopus", "octopus"),
+    e("🐳", "spouting whale", "whale docker"),
+    e("🐟", "fish", "fish"),
+    e("🦄", "unicorn", "unicorn"),
+    e("🌵", "cactus", "cactus desert"),
+    e("🌲", "evergreen tree", "tree pine"),
+    e("🌳", "deciduous tree", "tree"),
+    e("🍀", "four leaf clover", "luck clover"),
+    e("🌹", "rose", "flower rose"),
+    e("🌻", "sunflower", "flower sun"),
+    e("🌸", "cherry blossom", "flower spring"),
+    // Weather & sky
+    e("☀️", "sun", "sunny weather"),
+    e("🌙", "crescent moon", "moon night"),
+    e("⭐", "star", "star"),
+    e("🌟", "glowing star", "star sparkle"),
+    e("✨", "sparkles", "sparkle magic shiny"),
+    e("⚡", "high voltage", "lightning zap electric fast"),
+    e("🔥", "fire", "fire hot lit flame"),
+    e("🌈", "rainbow", "rainbow"),
+    e("☁️", "cloud", "cloud weather"),
+    e("🌧️", "cloud with rain", "rain weather"),
+    e("❄️", "snowflake", "snow cold winter"),
+    e("🌊", "water wave", "wave ocean sea"),
+    e("🌍", "globe showing Europe-Africa", "earth world globe"),
+    // Food & drink
+    e("🍎", "red apple", "apple fruit"),
+    e("🍌", "banana", "banana fruit"),
+    e("🍇", "grapes", "grapes fruit"),
+    e("🍓", "strawberry", "strawberry fruit"),
+    e("🍋", "lemon", "lemon fruit"),
+    e("🥑", "avocado", "avocado"),
+    e("🌶️", "hot pepper", "chili spicy"),
+    e("🍕", "pizza", "pizza food"),
+    e("🍔", "hamburger", "burger food"),
+    e("🍟", "french fries", "fries food"),
+    e("🌮", "taco", "taco food"),
+    e("🍣", "sushi", "sushi food"),
+    e("🍜", "steaming bowl", "noodles ramen"),
+    e("🍰", "shortcake", "cake dessert"),
+    e("🎂", "birthday cake", "birthday cake party"),
+    e("🍪", "cookie", "cookie dessert"),
+    e("🍩", "doughnut", "donut dessert"),
+    e("☕", "hot beverage", "coffee tea"),
+    e("🍵", "teacup without handle", "tea green"),
+    e("🍺", "beer mug", "beer drink"),
+    e("🍻", "clinking beer mugs", "cheers beer"),
+    e("🍷", "wine glass", "wine drink"),
+    e("🥂", "clinking glasses", "cheers celebrate toast"),
+    // Activities & objects
+    e("🎉", "party popper", "party tada celebrate"),
+    e("🎊", "confetti ball", "party celebrate"),
+    e("🎁", "wrapped gift", "present gift birthday"),
+    e("🏆", "trophy", "win award prize"),
+    e("🥇", "1st place medal", "gold first winner"),
+    e("⚽", "soccer ball", "football soccer"),
+    e("🏀", "basketball", "basketball"),
+    e("🎮", "video game", "game controller gaming"),
+    e("🎲", "game die", "dice random"),
+    e("🎯", "bullseye", "target goal dart"),
+    e("🎵", "musical note", "music note"),
+    e("🎧", "headphone", "music headphones"),
+    e("🎸", "guitar", "music guitar"),
+    e("📷", "camera", "photo camera"),
+    e("🎬", "clapper board", "movie film"),
+    e("💻", "laptop", "computer laptop"),
+    e("🖥️", "desktop computer", "computer monitor"),
+    e("⌨️", "keyboard", "keyboard typing"),
+    e("🖱️", "computer mouse", "mouse click"),
+    e("📱", "mobile phone", "phone smartphone"),
+    e("🔋", "battery", "battery power"),
+    e("🔌", "electric plug", "plug power"),
+    e("💾", "floppy disk", "save disk"),
+    e("💿", "optical disk", "cd disk"),
+    e("📦", "package", "box package shipping crate"),
+    e("📁", "file folder", "folder directory"),
+    e("📄", "page facing up", "document file page"),
+    e("📝", "memo", "note write todo"),
+    e("📌", "pushpin", "pin"),
+    e("📎", "paperclip", "attachment clip"),
+    e("✂️", "scissors", "cut"),
+    e("🔒", "locked", "lock secure private"),
+    e("🔓", "unlocked", "unlock open"),
+    e("🔑", "key", "key password"),
+    e("🔨", "hammer", "tool build"),
+    e("🔧", "wrench", "tool fix settings"),
+    e("⚙️", "gear", "settings cog config"),
+    e("🧪", "test tube", "test science experiment"),
+    e("🔬", "microscope", "science research"),
+    e("💡", "light bulb", "idea tip"),
+    e("🔍", "magnifying glass tilted left", "search find zoom"),
+    e("📚", "books", "library read study"),
+    e("📅", "calendar", "date calendar"),
+    e("📈", "chart increasing", "graph growth up"),
+    e("📉", "chart decreasing", "graph down"),
+    e("📊", "bar chart", "graph stats"),
+    e("📋", "clipboard", "clipboard paste"),
+    e("📧", "e-mail", "email mail"),
+    e("✉️", "envelope", "mail letter"),
+    e("🔔", "bell", "notification alert"),
+    e("🔕", "bell with slash", "mute silent"),
+    e("📣", "megaphone", "announce"),
+    e("⏰", "alarm clock", "alarm time"),
+    e("⏳", "hourglass not done", "time wait loading"),
+    e("⌛", "hourglass done", "time"),
+    e("⏱️", "stopwatch", "timer time"),
+    e("💰", "money bag", "money rich"),
+    e("💸", "money with wings", "money spend"),
+    e("💳", "credit card", "card payment"),
+    e("🚀", "rocket", "launch ship deploy fast"),
+    e("✈️", "airplane", "plane travel flight"),
+    e("🚗", "automobile", "car drive"),
+    e("🚲", "bicycle", "bike cycle"),
+    e("🚧", "construction", "wip work in progress"),
+    e("🚨", "police car light", "alert siren emergency"),
+    e("🏠", "house", "home"),
+    e("🏢", "office building", "office work"),
+    // Symbols
+    e("✅", "check mark button", "done yes ok tick"),
+    e("✔️", "check mark", "done yes tick"),
+    e("☑️", "check box with check", "done checkbox"),
+    e("❌", "cross mark", "no wrong delete x"),
+    e("❎", "cross mark button", "no x"),
+    e("➕", "plus", "add plus"),
+    e("➖", "minus", "subtract minus"),
+    e("➗", "divide", "division"),
+    e("✖️", "multiply", "times multiplication"),
+    e("❓", "red question mark", "question"),
+    e("❗", "red exclamation mark", "exclamation important"),
+    e("⚠️", "warning", "warning caution alert"),
+    e("⛔", "no entry", "forbidden stop"),
+    e("🚫", "prohibited", "forbidden no"),
+    e("♻️", "recycling symbol", "recycle"),
+    e("🔴", "red circle", "red dot"),
+    e("🟢", "green circle", "green dot"),
+    e("🔵", "blue circle", "blue dot"),
+    e("🟡", "yellow circle", "yellow dot"),
+    e("⬆️", "up arrow", "up arrow"),
+    e("⬇️", "down arrow", "down arrow"),
+    e("➡️", "right arrow", "right arrow"),
+    e("⬅️", "left arrow", "left arrow"),
+    e("🔄", "counterclockwise arrows button", "refresh reload sync"),
+    e("🆗", "OK button", "ok"),
+    e("🆕", "NEW button", "new"),
+    e("🆒", "COOL button", "cool"),
+    e("🏁", "chequered flag", "finish race"),
+    e("🚩", "triangular flag", "flag red"),
+    e("🏳️‍🌈", "rainbow flag", "pride lgbt"),
+    // Typographic & technical symbols
+    e("→", "rightwards arrow", "arrow right"),
+    e("←", "leftwards arrow", "arrow left"),
+    e("↑", "upwards arrow", "arrow up"),
+    e("↓", "downwards arrow", "arrow down"),
+    e("↔", "left right arrow", "arrow both"),
+    e("⇒", "rightwards double arrow", "implies arrow"),
+    e("⇔", "left right double arrow", "iff equivalent"),
+    e("↵", "downwards arrow with corner leftwards", "enter return"),
+    e("⌘", "place of interest sign", "command cmd mac"),
+    e("⌥", "option key", "option alt mac"),
+    e("⇧", "upwards white arrow", "shift key"),
+    e("⌫", "erase to the left", "backspace delete"),
+    e("⎋", "broken circle with northwest arrow", "escape esc"),
+    e("•", "bullet", "dot list"),
+    e("…", "horizontal ellipsis", "dots ellipsis"),
+    e("—", "em dash", "dash"),
+    e("–", "en dash", "dash range"),
+    e("«", "left-pointing double angle quotation mark", "guillemet quote"),
+    e("»", "right-pointing double angle quotation mark", "guillemet quote"),
+    e("“", "left double quotation mark", "quote"),
+    e("”", "right double quotation mark", "quote"),
+    e("‘", "left single quotation mark", "quote"),
+    e("’", "right single quotation mark", "apostrophe quote"),
+    e("§", "section sign", "section paragraph"),
+    e("¶", "pilcrow sign", "paragraph"),
+    e("†", "dagger", "footnote"),
+    e("©", "copyright sign", "copyright"),
+    e("®", "registered sign", "registered trademark"),
+    e("™", "trade mark sign", "trademark tm"),
+    e("°", "degree sign", "degree temperature"),
+    e("±", "plus-minus sign", "plus minus tolerance"),
+    e("×", "multiplication sign", "times multiply"),
+    e("÷", "division sign", "divide"),
+    e("≈", "almost equal to", "approximately"),
+    e("≠", "not equal to", "not equal"),
+    e("≤", "less-than or equal to", "less equal"),
+    e("≥", "greater-than or equal to", "greater equal"),
+    e("∞", "infinity", "infinite"),
+    e("√", "square root", "root sqrt"),
+    e("∑", "n-ary summation", "sum sigma"),
+    e("∏", "n-ary product", "product pi"),
+    e("∫", "integral", "integral calculus"),
+    e("∂", "partial differential", "partial derivative"),
+    e("∆", "increment", "delta change"),
+    e("∇", "nabla", "gradient del"),
+    e("∈", "element of", "in member set"),
+    e("∉", "not an element of", "not in set"),
+    e("∀", "for all", "forall universal"),
+    e("∃", "there exists", "exists"),
+    e("∅", "empty set", "empty null"),
+    e("∩", "intersection", "set and"),
+    e("∪", "union", "set or"),
+    e("¬", "not sign", "not negation"),
+    e("∧", "logical and", "and conjunction"),
+    e("∨", "logical or", "or disjunction"),
+    e("⊕", "circled plus", "xor direct sum"),
+    e("λ", "greek small letter lambda", "lambda"),
+    e("π", "greek small letter pi", "pi"),
+    e("µ", "micro sign", "mu micro"),
+    e("Ω", "ohm sign", "omega ohm"),
+    e("α", "greek small letter alpha", "alpha"),
+    e("β", "greek small letter beta", "beta"),
+    e("γ", "greek small letter gamma", "gamma"),
+    e("δ", "greek small letter delta", "delta"),
+    e("ε", "greek small letter epsilon", "epsilon"),
+    e("θ", "greek small letter theta", "theta"),
+    e("σ", "greek small letter sigma", "sigma"),
+    e("€", "euro sign", "euro currency money"),
+    e("£", "pound sign", "pound sterling currency"),
+    e("¥", "yen sign", "yen yuan currency"),
+    e("₹", "indian rupee sign", "rupee currency"),
+    e("₿", "bitcoin sign", "bitcoin btc currency"),
+    e("¢", "cent sign", "cent currency"),
+    e("½", "vulgar fraction one half", "half fraction"),
+    e("¼", "vulgar fraction one quarter", "quarter fraction"),
+    e("¾", "vulgar fraction three quarters", "three quarters fraction"),
+    e("²", "superscript two", "squared power"),
+    e("³", "superscript three", "cubed power"),
+    e("✓", "check mark", "tick done"),
+    e("✗", "ballot x", "cross wrong"),
+    e("★", "black star", "star filled"),
+    e("☆", "white star", "star outline"),
+    e("♥", "black heart suit", "heart card"),
+    e("♪", "eighth note", "music note"),
+    e("☐", "ballot box", "checkbox empty"),
+    e("☒", "ballot box with x", "checkbox crossed"),
+    e("\u{00a0}", "no-break space", "nbsp space"),
+    e("\u{200b}", "zero width space", "zwsp invisible"),
+];
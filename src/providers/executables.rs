@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use gio::prelude::AppInfoExt;
+use crate::config::cache_path;
+use crate::entry::{Entry, EntryKind};
+use crate::providers::files::display_path;
+
+const CACHE_FILE: &str = "path_index";
+
+/// Executables on `$PATH`. A name found in an earlier directory shadows later
+/// ones, matching how the shell resolves commands.
+///
+/// The result is cached on disk together with the modification time of every
+/// `$PATH` directory; adding or removing a file changes its directory's mtime,
+/// so any difference triggers a rescan.
+pub fn scan_path() -> Vec<PathBuf> {
+    let dirs: Vec<PathBuf> = match env::var_os("PATH") {
+        Some(path) => env::split_paths(&path).collect(),
+        None => return Vec::new(),
+    };
+    scan_cached(dirs, cache_path(CACHE_FILE).ok().as_deref())
+}
+
+/// Executables in `dirs`, from `cache` while none of the directories changed.
+fn scan_cached(dirs: Vec<PathBuf>, cache: Option<&Path>) -> Vec<PathBuf> {
+    let stamps = dir_stamps(&dirs);
+    if let Some(found) = cache.and_then(|path| read_cache(path, &stamps)) {
+        return found;
+    }
+
+    let found = scan_dirs(dirs);
+    if let Some(path) = cache
+        && let Err(e) = write_cache(path, &stamps, &found)
+    {
+        eprintln!("Failed to write PATH cache: {e}");
+    }
+    found
+}
+
+/// Executables on `$PATH` that no desktop entry already launches, compared by
+/// file name so `/usr/bin/firefox` is hidden when `firefox.desktop` exists.
+pub fn entries(apps: &[Entry]) -> Vec<Entry> {
+    uncovered(apps, scan_path())
+}
+
+fn uncovered(apps: &[Entry], found: Vec<PathBuf>) -> Vec<Entry> {
+    let covered: HashSet<String> = apps
+        .iter()
+        .filter_map(|entry| match &entry.kind {
+            EntryKind::App(app) => app.executable().file_name().map(|n| n.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+
+    found
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| !covered.contains(name.to_string_lossy().as_ref()))
+        })
+        .map(|path| {
+            let subtitle = display_path(&path);
+            Entry::executable(path).with_subtitle(subtitle)
+        })
+        .collect()
+}
+
+/// One `dir<TAB>mtime` line per `$PATH` directory, in order. Missing
+/// directories are recorded with an empty mtime so creating one invalidates too.
+fn dir_stamps(dirs: &[PathBuf]) -> Vec<String> {
+    dirs.iter()
+        .map(|dir| {
+            let mtime = fs::metadata(dir)
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|since| since.as_nanos().to_string())
+                .unwrap_or_default();
+            format!("{}\t{}", dir.display(), mtime)
+        })
+        .collect()
+}
+
+/// The cache holds the directory stamps, a blank line, then one path per line.
+fn read_cache(path: &Path, stamps: &[String]) -> Option<Vec<PathBuf>> {
+    let raw = fs::read_to_string(path).ok()?;
+    let (header, body) = raw.split_once("\n\n")?;
+    if !header.lines().eq(stamps.iter().map(String::as_str)) {
+        return None;
+    }
+    Some(body.lines().map(PathBuf::from).collect())
+}
+
+fn write_cache(path: &Path, stamps: &[String], found: &[PathBuf]) -> std::io::Result<()> {
+    let mut out = stamps.join("\n");
+    out.push_str("\n\n");
+    for exe in found {
+        out.push_str(&exe.to_string_lossy());
+        out.push('\n');
+    }
+    fs::write(path, out)
+}
+
+fn scan_dirs(dirs: impl IntoIterator<Item = PathBuf>) -> Vec<PathBuf> {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut found = Vec::new();
+
+    for dir in dirs {
+        let Ok(read_dir) = fs::read_dir(&dir) else { continue };
+        for dirent in read_dir.flatten() {
+            let name = dirent.file_name().to_string_lossy().into_owned();
+            let path = dirent.path();
+            if !seen.contains(&name) && is_executable(&path) {
+                seen.insert(name);
+                found.push(path);
+            }
+        }
+    }
+
+    found.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    found
+}
+
+/// Follows symlinks, so AppImages linked into `~/.local/bin` count.
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gio::{AppInfo, AppInfoCreateFlags};
+
+    fn write_file(path: &Path, mode: u32) {
+        fs::write(path, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn scan_skips_non_executables_and_shadowed_names() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let (first, second) = (root.join("first"), root.join("second"));
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+
+        write_file(&first.join("tool"), 0o755);
+        write_file(&first.join("notes.txt"), 0o644);
+        write_file(&second.join("tool"), 0o755);
+        write_file(&second.join("other"), 0o700);
+
+        let found = scan_dirs([first.clone(), second.clone()]);
+        assert_eq!(found, vec![second.join("other"), first.join("tool")]);
+    }
+
+    #[test]
+    fn rescans_once_a_directory_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let bin = root.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        write_file(&bin.join("tool"), 0o755);
+
+        let cache = root.join("path_index");
+        let dirs = vec![bin.clone()];
+        assert_eq!(scan_cached(dirs.clone(), Some(&cache)), vec![bin.join("tool")]);
+        assert_eq!(read_cache(&cache, &dir_stamps(&dirs)), Some(vec![bin.join("tool")]));
+
+        write_file(&bin.join("added"), 0o755);
+        // Two writes within the filesystem's timestamp granularity share an
+        // mtime, so move the directory's well away from the cached one.
+        let old = UNIX_EPOCH + std::time::Duration::from_secs(1);
+        fs::File::open(&bin).unwrap().set_modified(old).unwrap();
+        assert_eq!(scan_cached(dirs, Some(&cache)), vec![bin.join("added"), bin.join("tool")]);
+    }
+
+    #[test]
+    fn executables_launched_by_a_desktop_entry_are_left_out() {
+        let firefox =
+            AppInfo::create_from_commandline("/usr/lib/firefox/firefox %u", Some("Firefox"), AppInfoCreateFlags::NONE)
+                .unwrap();
+        let apps = vec![Entry::from_app(firefox)];
+        let found = vec![PathBuf::from("/usr/bin/firefox"), PathBuf::from("/usr/bin/htop")];
+
+        let titles: Vec<String> = uncovered(&apps, found).into_iter().map(|e| e.title).collect();
+        assert_eq!(titles, ["htop"]);
+    }
+}
@@ -0,0 +1,438 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use directories::BaseDirs;
+use crate::config::{Config, FileSearch};
+use crate::entry::Entry;
+use crate::glob::glob_match;
+use crate::search::fuzzy_rank;
+
+/// Fuzzy search over the background index, e.g. `f notes`.
+pub const INDEX_PREFIX: &str = "f ";
+
+/// Paths are pushed to the shared index in batches to keep lock contention low.
+const BATCH_SIZE: usize = 512;
+
+/// How long an index is trusted before the next `f` query walks the roots again.
+const REFRESH_AFTER: Duration = Duration::from_secs(60);
+
+/// How often the window checks a running walk for new paths.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Searches files under the configured roots. Queries starting with `/` or `~/`
+/// browse that directory directly; `f <query>` matches against a background index
+/// that grows while the walk is in progress.
+#[derive(Default)]
+pub struct FileIndex {
+    paths: Arc<Mutex<Vec<PathBuf>>>,
+    walking: Arc<AtomicBool>,
+    walked_at: Cell<Option<Instant>>,
+    /// The pattern of the `f` query on screen, if any.
+    pattern: Rc<RefCell<Option<String>>>,
+    notify: RefCell<Option<Rc<dyn Fn()>>>,
+}
+
+impl FileIndex {
+    pub fn search(&self, query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+        if let Some(pattern) = query.strip_prefix(INDEX_PREFIX) {
+            let pattern = pattern.trim();
+            *self.pattern.borrow_mut() = Some(pattern.to_string());
+            self.ensure_fresh(&cfg.files);
+            return Some(self.search_index(pattern, cfg));
+        }
+        *self.pattern.borrow_mut() = None;
+
+        if query.starts_with('/') || query.starts_with("~/") {
+            return Some(browse(query, cfg));
+        }
+
+        None
+    }
+
+    /// Sets what to do when a walk finds paths matching the `f` query on screen.
+    pub fn on_update(&self, notify: Rc<dyn Fn()>) {
+        *self.notify.borrow_mut() = Some(notify);
+    }
+
+    /// Walks the roots on first use, so launches that never search files pay
+    /// nothing for it, and again once the index is older than [`REFRESH_AFTER`].
+    /// The first walk fills the index as it goes; later ones replace it when
+    /// done, so the old index stays searchable meanwhile.
+    fn ensure_fresh(&self, opts: &FileSearch) {
+        let fresh = self.walked_at.get().is_some_and(|at| at.elapsed() < REFRESH_AFTER);
+        if fresh || self.walking.load(Ordering::Acquire) {
+            return;
+        }
+        let first = self.walked_at.replace(Some(Instant::now())).is_none();
+        self.walking.store(true, Ordering::Release);
+
+        let roots: Vec<PathBuf> = opts.roots.iter().map(|r| expand_tilde(r)).collect();
+        let opts = opts.clone();
+        let paths = self.paths.clone();
+        let walking = self.walking.clone();
+        thread::spawn(move || {
+            if first {
+                walk(roots, &opts, &paths);
+            } else {
+                let out = Mutex::new(Vec::new());
+                walk(roots, &opts, &out);
+                let out = out.into_inner().unwrap_or_else(|e| e.into_inner());
+                *paths.lock().unwrap_or_else(|e| e.into_inner()) = out;
+            }
+            walking.store(false, Ordering::Release);
+        });
+        self.watch_walk();
+    }
+
+    /// Shows the `f` query again whenever the running walk adds paths that
+    /// match it, and once more when a walk finishes.
+    fn watch_walk(&self) {
+        let Some(notify) = self.notify.borrow().clone() else { return };
+        let paths = self.paths.clone();
+        let walking = self.walking.clone();
+        let pattern = self.pattern.clone();
+        let mut seen = paths.lock().unwrap_or_else(|e| e.into_inner()).len();
+
+        glib::MainContext::default().spawn_local(async move {
+            loop {
+                glib::timeout_future(POLL_INTERVAL).await;
+                let done = !walking.load(Ordering::Acquire);
+                let matched = {
+                    let paths = paths.lock().unwrap_or_else(|e| e.into_inner());
+                    let new = paths.get(seen..).unwrap_or_default();
+                    seen = paths.len();
+                    pattern.borrow().as_deref().is_some_and(|pattern| {
+                        !pattern.is_empty()
+                            && (done || !fuzzy_rank(new.iter().map(|p| display_path(p)), pattern, 1, |d| d).is_empty())
+                    })
+                };
+                // The lock is released first: showing the query searches the index again.
+                if matched {
+                    notify();
+                }
+                if done {
+                    break;
+                }
+            }
+        });
+    }
+
+    fn search_index(&self, pattern: &str, cfg: &Config) -> Vec<Entry> {
+        if pattern.is_empty() {
+            return Vec::new();
+        }
+
+        let paths = self.paths.lock().unwrap_or_else(|e| e.into_inner());
+        let candidates = paths.iter().map(|p| (display_path(p), p));
+        fuzzy_rank(candidates, pattern, cfg.max_results, |(display, _)| display)
+            .into_iter()
+            .map(|(display, path)| Entry::file(path.clone(), display))
+            .collect()
+    }
+}
+
+/// Breadth-first walk so shallow paths become searchable first.
+fn walk(roots: Vec<PathBuf>, opts: &FileSearch, out: &Mutex<Vec<PathBuf>>) {
+    let mut queue: VecDeque<(PathBuf, usize)> = roots.into_iter().map(|root| (root, 0)).collect();
+    let mut batch: Vec<PathBuf> = Vec::with_capacity(BATCH_SIZE);
+    let mut total = 0;
+
+    let flush = |batch: &mut Vec<PathBuf>| {
+        out.lock().unwrap_or_else(|e| e.into_inner()).append(batch);
+    };
+
+    while let Some((dir, depth)) = queue.pop_front() {
+        let Ok(read_dir) = fs::read_dir(&dir) else { continue };
+
+        for dirent in read_dir.flatten() {
+            let name = dirent.file_name();
+            if is_ignored(&name.to_string_lossy(), opts) {
+                continue;
+            }
+
+            // file_type() does not follow symlinks, which keeps the walk free of cycles.
+            let path = dirent.path();
+            if dirent.file_type().is_ok_and(|t| t.is_dir()) && depth + 1 < opts.max_depth {
+                queue.push_back((path.clone(), depth + 1));
+            }
+
+            batch.push(path);
+            total += 1;
+            if batch.len() >= BATCH_SIZE {
+                flush(&mut batch);
+            }
+            if total >= opts.max_indexed {
+                flush(&mut batch);
+                return;
+            }
+        }
+    }
+
+    flush(&mut batch);
+}
+
+fn is_ignored(name: &str, opts: &FileSearch) -> bool {
+    if !opts.show_hidden && name.starts_with('.') {
+        return true;
+    }
+    opts.ignore.iter().any(|pattern| glob_match(pattern, name))
+}
+
+/// Lists the directory named by everything up to the last `/` and fuzzy-matches
+/// the remainder against its children.
+fn browse(query: &str, cfg: &Config) -> Vec<Entry> {
+    let split = query.rfind('/').map_or(0, |i| i + 1);
+    let (dir_part, pattern) = query.split_at(split);
+    let dir = expand_tilde(dir_part);
+
+    let Ok(read_dir) = fs::read_dir(&dir) else { return Vec::new() };
+
+    let show_hidden = cfg.files.show_hidden || pattern.starts_with('.');
+    let mut children: Vec<(String, PathBuf)> = read_dir
+        .flatten()
+        .filter_map(|dirent| {
+            let name = dirent.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') && !show_hidden {
+                return None;
+            }
+            let path = dirent.path();
+            let title = if path.is_dir() {
+                format!("{}{}/", dir_part, name)
+            } else {
+                format!("{}{}", dir_part, name)
+            };
+            Some((title, path))
+        })
+        .collect();
+
+    if pattern.is_empty() {
+        // Directories first, then alphabetical, like a file chooser.
+        children.sort_by(|a, b| b.1.is_dir().cmp(&a.1.is_dir()).then_with(|| a.0.cmp(&b.0)));
+        return children
+            .into_iter()
+            .take(cfg.max_results)
+            .map(|(title, path)| Entry::file(path, title))
+            .collect();
+    }
+
+    let prefix_len = dir_part.len();
+    fuzzy_rank(children, pattern, cfg.max_results, |(title, _)| &title[prefix_len..])
+        .into_iter()
+        .map(|(title, path)| Entry::file(path, title))
+        .collect()
+}
+
+fn home_dir() -> Option<PathBuf> {
+    BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf())
+}
+
+/// Expands a leading `~` to the user's home directory.
+pub fn expand_tilde(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Whether a query argument names a file rather than being part of an app's
+/// name: `/…`, `~/…`, `./…`, `../…`, `.` or `..`.
+pub fn looks_like_path(arg: &str) -> bool {
+    ["/", "~/", "./", "../"].iter().any(|prefix| arg.starts_with(prefix)) || matches!(arg, "~" | "." | "..")
+}
+
+/// Expands `~` and resolves relative paths against home, since scout's own
+/// working directory is whatever it happened to be started from.
+pub fn resolve_path(arg: &str) -> PathBuf {
+    let path = expand_tilde(arg);
+    match home_dir() {
+        Some(home) if path.is_relative() => home.join(path),
+        _ => path,
+    }
+}
+
+/// Completes a typed path as far as it is unambiguous: to the one matching
+/// child (with a trailing `/` for a directory), or to the longest prefix all
+/// matches share. `None` when there is nothing to add.
+pub fn complete_path(partial: &str) -> Option<String> {
+    if partial == "~" {
+        return Some("~/".to_string());
+    }
+    let split = partial.rfind('/').map_or(0, |i| i + 1);
+    let (dir_part, prefix) = partial.split_at(split);
+    let dir = resolve_path(if dir_part.is_empty() { "." } else { dir_part });
+
+    let matches: Vec<(String, bool)> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|dirent| (dirent.file_name().to_string_lossy().into_owned(), dirent.path().is_dir()))
+        .filter(|(name, _)| name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.')))
+        .collect();
+
+    let completed = match matches.as_slice() {
+        [] => return None,
+        [(name, is_dir)] => format!("{}{}{}", dir_part, name, if *is_dir { "/" } else { "" }),
+        [(first, _), rest @ ..] => {
+            let mut common = first.clone();
+            for (name, _) in rest {
+                let shared = common.chars().zip(name.chars()).take_while(|(a, b)| a == b).map(|(a, _)| a.len_utf8()).sum();
+                common.truncate(shared);
+            }
+            format!("{}{}", dir_part, common)
+        }
+    };
+    (completed != partial).then_some(completed)
+}
+
+/// Abbreviates the home directory to `~` for display and matching.
+pub fn display_path(path: &Path) -> String {
+    if let Some(home) = home_dir()
+        && let Ok(rest) = path.strip_prefix(&home)
+    {
+        return format!("~/{}", rest.display());
+    }
+    path.display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_tree() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("docs/deep/deeper")).unwrap();
+        fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        fs::write(root.join("docs/notes.md"), "").unwrap();
+        fs::write(root.join("docs/deep/deeper/buried.txt"), "").unwrap();
+        fs::write(root.join(".hidden"), "").unwrap();
+        fs::write(root.join("main.o"), "").unwrap();
+        tmp
+    }
+
+    fn indexed(root: &Path, opts: &FileSearch) -> Vec<PathBuf> {
+        let out = Mutex::new(Vec::new());
+        walk(vec![root.to_path_buf()], opts, &out);
+        out.into_inner().unwrap()
+    }
+
+    #[test]
+    fn walk_respects_ignore_rules_and_depth() {
+        let tmp = temp_tree();
+        let root = tmp.path();
+        let opts = FileSearch { max_depth: 2, ..FileSearch::default() };
+        let paths = indexed(root, &opts);
+
+        assert!(paths.contains(&root.join("docs/notes.md")));
+        assert!(!paths.contains(&root.join("docs/deep/deeper/buried.txt")));
+        assert!(!paths.iter().any(|p| p.starts_with(root.join("node_modules"))));
+        assert!(!paths.contains(&root.join(".hidden")));
+        assert!(!paths.contains(&root.join("main.o")));
+    }
+
+    #[test]
+    fn walk_stops_at_max_indexed() {
+        let tmp = temp_tree();
+        let root = tmp.path();
+        let opts = FileSearch { max_indexed: 2, ..FileSearch::default() };
+        assert_eq!(indexed(root, &opts).len(), 2);
+    }
+
+    #[test]
+    fn browse_matches_children_of_typed_directory() {
+        let tmp = temp_tree();
+        let root = tmp.path();
+        let cfg = Config::default();
+        let query = format!("{}/docs/nts", root.display());
+        let results = browse(&query, &cfg);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, format!("{}/docs/notes.md", root.display()));
+    }
+
+    #[test]
+    fn browse_lists_directories_first() {
+        let tmp = temp_tree();
+        let root = tmp.path();
+        let cfg = Config::default();
+        let results = browse(&format!("{}/docs/", root.display()), &cfg);
+
+        assert_eq!(results[0].title, format!("{}/docs/deep/", root.display()));
+        assert_eq!(results[1].title, format!("{}/docs/notes.md", root.display()));
+    }
+
+    #[test]
+    fn complete_path_extends_unambiguous_prefixes() {
+        let tmp = temp_tree();
+        let root = tmp.path();
+        fs::write(root.join("docs/notes-old.md"), "").unwrap();
+        let dir = root.display();
+
+        assert_eq!(complete_path(&format!("{dir}/do")), Some(format!("{dir}/docs/")));
+        assert_eq!(complete_path(&format!("{dir}/docs/no")), Some(format!("{dir}/docs/notes")));
+        assert_eq!(complete_path(&format!("{dir}/docs/notes")), None);
+        assert_eq!(complete_path(&format!("{dir}/missing/x")), None);
+        // Dotfiles only complete once a `.` is typed
+        assert_eq!(complete_path(&format!("{dir}/.h")), Some(format!("{dir}/.hidden")));
+        assert!(looks_like_path("~/Pictures") && looks_like_path(".") && !looks_like_path("office"));
+    }
+
+    #[test]
+    fn a_stale_index_is_walked_again() {
+        let tmp = temp_tree();
+        let mut cfg = Config::default();
+        cfg.files.roots = vec![tmp.path().display().to_string()];
+        let index = FileIndex::default();
+        index.search("f notes", &cfg);
+        while index.walking.load(Ordering::Acquire) {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        fs::write(tmp.path().join("docs/later.md"), "").unwrap();
+        assert!(index.search("f later", &cfg).unwrap().is_empty());
+
+        index.walked_at.set(Instant::now().checked_sub(REFRESH_AFTER));
+        index.search("f later", &cfg);
+        while index.walking.load(Ordering::Acquire) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(index.search("f later", &cfg).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn a_walk_shows_new_matches_for_the_query() {
+        let tmp = temp_tree();
+        let mut cfg = Config::default();
+        cfg.files.roots = vec![tmp.path().display().to_string()];
+        let index = FileIndex::default();
+        let woken = Rc::new(Cell::new(false));
+        index.on_update(Rc::new({
+            let woken = woken.clone();
+            move || woken.set(true)
+        }));
+
+        let context = glib::MainContext::default();
+        let _owner = context.acquire().unwrap();
+        index.search("f notes", &cfg);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !woken.get() && Instant::now() < deadline {
+            context.iteration(true);
+        }
+
+        assert!(woken.get());
+        assert_eq!(index.search("f notes", &cfg).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn non_path_queries_are_not_claimed() {
+        let index = FileIndex::default();
+        assert!(index.search("firefox", &Config::default()).is_none());
+    }
+}
@@ -0,0 +1,132 @@
+use std::path::{Path, PathBuf};
+use gio::prelude::{AppInfoExt, FileExt};
+use gio::{AppInfo, FileQueryInfoFlags};
+use crate::config::Config;
+use crate::entry::Entry;
+use crate::providers::files::{display_path, expand_tilde};
+
+/// Lists every application registered for a typed or pasted file path or URL,
+/// with the default handler first.
+pub fn search(query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+    let (uri, label, apps) = match parse_query(query)? {
+        Target::Url { scheme } => {
+            let apps = handlers(
+                AppInfo::default_for_uri_scheme(&scheme),
+                AppInfo::all_for_type(&format!("x-scheme-handler/{}", scheme)),
+            );
+            let url = query.trim().to_string();
+            (url.clone(), url, apps)
+        }
+        Target::File(path) => {
+            if !path.is_file() {
+                return None;
+            }
+            let file = gio::File::for_path(&path);
+            let content_type = content_type(&file, &path);
+            let apps = handlers(
+                AppInfo::default_for_type(&content_type, false),
+                AppInfo::all_for_type(&content_type),
+            );
+            (file.uri().to_string(), display_path(&path), apps)
+        }
+    };
+
+    Some(
+        apps.into_iter()
+            .take(cfg.max_results)
+            .map(|app| {
+                let title = format!("Open with {}", app.display_name());
+                Entry::open_with(app, uri.clone(), title).with_subtitle(label.clone())
+            })
+            .collect(),
+    )
+}
+
+/// What a query names, before checking that it exists.
+#[derive(Debug, PartialEq)]
+enum Target {
+    Url { scheme: String },
+    File(PathBuf),
+}
+
+/// A URL, a `file://` URI or a path starting with `/` or `~/`. Paths may
+/// contain spaces; other URLs must be a single word, so queries such as
+/// `firefox https://…` are left to the other providers.
+fn parse_query(query: &str) -> Option<Target> {
+    let query = query.trim();
+    match uri_scheme(query) {
+        Some(scheme) if scheme == "file" => gio::File::for_uri(query).path().map(Target::File),
+        Some(scheme) => (!query.contains(char::is_whitespace)).then_some(Target::Url { scheme }),
+        None if query.starts_with('/') || query.starts_with("~/") => Some(Target::File(expand_tilde(query))),
+        None => None,
+    }
+}
+
+/// Returns the lowercased scheme of `query` when it looks like a URL rather
+/// than a path or a `host:port` pair.
+fn uri_scheme(query: &str) -> Option<String> {
+    let scheme = glib::Uri::parse_scheme(query)?.to_string();
+    let rest = &query[scheme.len()..];
+    let scheme = scheme.to_ascii_lowercase();
+    if rest.starts_with("://") || scheme == "mailto" {
+        Some(scheme)
+    } else {
+        None
+    }
+}
+
+/// Sniffs the file contents when possible; falls back to the file name.
+fn content_type(file: &gio::File, path: &Path) -> String {
+    file.query_info(
+        gio::FILE_ATTRIBUTE_STANDARD_CONTENT_TYPE,
+        FileQueryInfoFlags::NONE,
+        None::<&gio::Cancellable>,
+    )
+    .ok()
+    .and_then(|info| info.content_type())
+    .map(String::from)
+    .unwrap_or_else(|| gio::content_type_guess(Some(path), &[]).0.to_string())
+}
+
+/// Default handler first, followed by every other registered application.
+fn handlers(default: Option<AppInfo>, all: Vec<AppInfo>) -> Vec<AppInfo> {
+    let mut apps: Vec<AppInfo> = default.into_iter().collect();
+    for app in all {
+        if app.should_show() && !apps.iter().any(|known| known.equal(&app)) {
+            apps.push(app);
+        }
+    }
+    apps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schemes_are_told_apart_from_paths_and_ports() {
+        assert_eq!(uri_scheme("https://example.com").as_deref(), Some("https"));
+        assert_eq!(uri_scheme("HTTPS://Example.com").as_deref(), Some("https"));
+        assert_eq!(uri_scheme("mailto:me@example.com").as_deref(), Some("mailto"));
+        assert_eq!(uri_scheme("file:///etc/hosts").as_deref(), Some("file"));
+        assert_eq!(uri_scheme("localhost:8080"), None);
+        assert_eq!(uri_scheme("/etc/hosts"), None);
+        assert_eq!(uri_scheme("notes.md"), None);
+    }
+
+    #[test]
+    fn queries_parse_to_urls_or_paths() {
+        assert_eq!(parse_query("  Https://example.com "), Some(Target::Url { scheme: "https".to_string() }));
+        assert_eq!(parse_query("file:///tmp/a%20b.txt"), Some(Target::File(PathBuf::from("/tmp/a b.txt"))));
+        assert_eq!(parse_query("/etc/hosts"), Some(Target::File(PathBuf::from("/etc/hosts"))));
+        assert_eq!(parse_query("~/notes.md"), Some(Target::File(expand_tilde("~/notes.md"))));
+        assert_eq!(
+            parse_query(" /tmp/My Documents/report 2.pdf "),
+            Some(Target::File(PathBuf::from("/tmp/My Documents/report 2.pdf")))
+        );
+        assert_eq!(parse_query(""), None);
+        assert_eq!(parse_query("notes.md"), None);
+        assert_eq!(parse_query("open /etc/hosts"), None);
+        assert_eq!(parse_query("https://example.com and more"), None);
+    }
+}
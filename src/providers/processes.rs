@@ -0,0 +1,185 @@
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use crate::config::Config;
+use crate::entry::{Entry, Process};
+use crate::procfs;
+use crate::search::fuzzy_rank;
+
+/// Running processes, e.g. `kill firefox`.
+pub const PREFIX: &str = "kill ";
+
+/// Processes whose loss takes down the session or the machine with them.
+const CRITICAL: &[&str] = &[
+    "systemd", "init", "Xorg", "Xwayland", "gnome-shell", "kwin_x11", "kwin_wayland", "plasmashell",
+    "sway", "Hyprland", "dbus-daemon", "dbus-broker", "pipewire", "wireplumber", "pulseaudio",
+    "gdm", "sddm", "lightdm", "login", "sshd",
+];
+
+/// Processes from `/proc`, read once per search session. Enter sends SIGTERM,
+/// Shift+Enter SIGKILL.
+#[derive(Default)]
+pub struct Processes {
+    processes: OnceCell<Vec<Process>>,
+}
+
+impl Processes {
+    pub fn search(&self, query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+        let pattern = query.strip_prefix(PREFIX)?.trim();
+        let processes = self.processes.get_or_init(|| list(Path::new(procfs::PROC_ROOT)));
+
+        let found: Vec<&Process> = if pattern.is_empty() {
+            let mut busiest: Vec<&Process> = processes.iter().collect();
+            busiest.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
+            busiest.into_iter().take(cfg.max_results).collect()
+        } else {
+            fuzzy_rank(processes.iter(), pattern, cfg.max_results, |process| &process.cmdline)
+        };
+        Some(found.into_iter().map(|process| entry(process.clone())).collect())
+    }
+}
+
+/// Other users' processes and critical ones are only signalled after confirmation.
+pub fn needs_confirmation(process: &Process) -> bool {
+    process.critical || process.uid != unsafe { libc::getuid() }
+}
+
+/// The rows shown instead of signalling right away.
+pub fn confirmation(process: &Process, signal: i32) -> Vec<Entry> {
+    let why = if process.critical {
+        "Critical system process".to_string()
+    } else {
+        format!("Owned by {}", process.user)
+    };
+    let title = format!("Send {} to {} (PID {})", signal_name(signal), process.name, process.pid);
+    vec![Entry::signal(process.clone(), signal, title).with_subtitle(why), Entry::cancel()]
+}
+
+pub fn signal_name(signal: i32) -> &'static str {
+    match signal {
+        libc::SIGKILL => "SIGKILL",
+        libc::SIGTERM => "SIGTERM",
+        _ => "signal",
+    }
+}
+
+fn entry(process: Process) -> Entry {
+    let subtitle = format!(
+        "PID {} · {:.1}% CPU · {} · {}",
+        process.pid,
+        process.cpu_percent,
+        format_kib(process.rss_kib),
+        process.user
+    );
+    Entry::process(process).with_subtitle(subtitle)
+}
+
+/// User-space processes under `root`; kernel threads have no command line and are left out.
+fn list(root: &Path) -> Vec<Process> {
+    let uptime = procfs::uptime(root).unwrap_or(0.0);
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as f64;
+    let users = read_users(Path::new("/etc/passwd"));
+
+    procfs::pids(root)
+        .into_iter()
+        .filter(|&pid| pid != std::process::id())
+        .filter_map(|pid| {
+            let cmdline = procfs::cmdline(root, pid);
+            if cmdline.is_empty() {
+                return None;
+            }
+            let status = procfs::status(root, pid)?;
+            Some(Process {
+                pid,
+                critical: pid == 1 || CRITICAL.contains(&status.name.as_str()),
+                user: users.get(&status.uid).cloned().unwrap_or_else(|| status.uid.to_string()),
+                name: status.name,
+                cmdline: cmdline.join(" "),
+                uid: status.uid,
+                cpu_percent: procfs::cpu_percent(root, pid, uptime, ticks).unwrap_or(0.0),
+                rss_kib: status.rss_kib,
+            })
+        })
+        .collect()
+}
+
+/// User names by uid, from a passwd-format file.
+fn read_users(path: &Path) -> HashMap<u32, String> {
+    let Ok(raw) = fs::read_to_string(path) else { return HashMap::new() };
+    raw.lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+fn format_kib(kib: u64) -> String {
+    if kib >= 1024 * 1024 {
+        format!("{:.1} GB", kib as f64 / (1024.0 * 1024.0))
+    } else if kib >= 1024 {
+        format!("{:.0} MB", kib as f64 / 1024.0)
+    } else {
+        format!("{} kB", kib)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_user_processes_from_fake_proc() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("1")).unwrap();
+        fs::create_dir_all(root.join("2")).unwrap();
+        fs::create_dir_all(root.join("300")).unwrap();
+        fs::write(root.join("uptime"), "1000.00 3000.00\n").unwrap();
+
+        fs::write(root.join("1/cmdline"), b"/sbin/init\0splash\0").unwrap();
+        fs::write(root.join("1/status"), "Name:\tsystemd\nUid:\t0\t0\t0\t0\nVmRSS:\t2048 kB\n").unwrap();
+        // A kernel thread: no command line.
+        fs::write(root.join("2/cmdline"), b"").unwrap();
+        fs::write(root.join("2/status"), "Name:\tkthreadd\nUid:\t0\t0\t0\t0\n").unwrap();
+        fs::write(root.join("300/cmdline"), b"python3\0server.py\0").unwrap();
+        fs::write(root.join("300/status"), "Name:\tpython3\nUid:\t1000\t1000\t1000\t1000\nVmRSS:\t51200 kB\n").unwrap();
+
+        let mut processes = list(root);
+        processes.sort_by_key(|p| p.pid);
+        assert_eq!(processes.len(), 2);
+        assert!(processes[0].critical);
+        assert_eq!(processes[1].cmdline, "python3 server.py");
+        assert_eq!(format_kib(processes[1].rss_kib), "50 MB");
+    }
+
+    #[test]
+    fn passwd_maps_uids_to_names() {
+        let passwd = tempfile::NamedTempFile::new().unwrap();
+        let path = passwd.path();
+        fs::write(path, "root:x:0:0:root:/root:/bin/sh\nalice:x:1000:1000::/home/alice:/bin/zsh\n").unwrap();
+        let users = read_users(path);
+        assert_eq!(users.get(&1000).map(String::as_str), Some("alice"));
+    }
+
+    #[test]
+    fn confirmation_offers_signal_and_cancel() {
+        let process = Process {
+            pid: 1,
+            name: "systemd".to_string(),
+            cmdline: "/sbin/init".to_string(),
+            uid: 0,
+            user: "root".to_string(),
+            cpu_percent: 0.0,
+            rss_kib: 0,
+            critical: true,
+        };
+        assert!(needs_confirmation(&process));
+        let rows = confirmation(&process, libc::SIGKILL);
+        assert_eq!(rows[0].title, "Send SIGKILL to systemd (PID 1)");
+        assert_eq!(rows[1].title, "Cancel");
+    }
+}
@@ -0,0 +1,138 @@
+use std::cell::{OnceCell, RefCell};
+use std::path::Path;
+use std::rc::Rc;
+use gio::prelude::AppInfoExt;
+use glib::Cast;
+use gtk::prelude::RecentManagerExt;
+use gtk::{RecentInfo, RecentManager};
+use crate::config::Config;
+use crate::entry::{Entry, RecentDoc};
+use crate::providers::files::display_path;
+use crate::search::fuzzy_rank;
+
+/// Recent documents, e.g. `r report`.
+pub const PREFIX: &str = "r ";
+
+/// Documents GTK applications recorded in `~/.local/share/recently-used.xbel`.
+/// The list is built on first use and dropped whenever the manager reports
+/// that the file changed, so the next query sees fresh data.
+#[derive(Default)]
+pub struct RecentFiles {
+    manager: OnceCell<Option<RecentManager>>,
+    docs: Rc<RefCell<Option<Vec<Entry>>>>,
+}
+
+impl RecentFiles {
+    pub fn search(&self, query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+        let pattern = query.strip_prefix(PREFIX)?.trim();
+
+        let manager = self.manager.get_or_init(|| {
+            let manager = RecentManager::default()?;
+            let docs = self.docs.clone();
+            manager.connect_changed(move |_| {
+                docs.borrow_mut().take();
+            });
+            Some(manager)
+        });
+        let manager = manager.as_ref()?;
+
+        let mut docs = self.docs.borrow_mut();
+        let docs = docs.get_or_insert_with(|| load(manager));
+        Some(matching(docs, pattern, cfg.max_results))
+    }
+}
+
+/// Up to `limit` of `docs`: the most recent ones for an empty `pattern`,
+/// otherwise the best fuzzy matches on the title.
+fn matching(docs: &[Entry], pattern: &str, limit: usize) -> Vec<Entry> {
+    if pattern.is_empty() {
+        return docs.iter().take(limit).cloned().collect();
+    }
+    fuzzy_rank(docs.iter(), pattern, limit, |e| &e.title).into_iter().cloned().collect()
+}
+
+/// Most recently modified first, skipping missing files and items marked private
+/// to the application that registered them.
+fn load(manager: &RecentManager) -> Vec<Entry> {
+    let mut items: Vec<RecentInfo> = manager
+        .items()
+        .into_iter()
+        .filter(|info| info.exists() && !info.is_private_hint())
+        .collect();
+    items.sort_by_key(|info| std::cmp::Reverse(info.modified()));
+    items.iter().filter_map(to_entry).collect()
+}
+
+fn to_entry(info: &RecentInfo) -> Option<Entry> {
+    let uri = info.uri()?.to_string();
+    let title = info.display_name().map(String::from).unwrap_or_else(|| uri.clone());
+    let mime_type = info
+        .mime_type()
+        .map(String::from)
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    let app = info
+        .last_application()
+        .and_then(|name| recorded_app(info, &name));
+
+    let location = match info.uri_display() {
+        Some(display) if display.starts_with('/') => display_path(Path::new(display.as_str())),
+        Some(display) => display.to_string(),
+        None => uri.clone(),
+    };
+    let subtitle = match &app {
+        Some(app) => format!("{} · {}", app.display_name(), location),
+        None => location,
+    };
+
+    Some(Entry::recent(RecentDoc { uri, mime_type, app }, title).with_subtitle(subtitle))
+}
+
+/// Prefers the installed desktop entry for the recorded application name and
+/// falls back to the command line GTK stored alongside the document.
+fn recorded_app(info: &RecentInfo, name: &str) -> Option<gio::AppInfo> {
+    if let Some(desktop) = gio::DesktopAppInfo::new(&format!("{}.desktop", name)) {
+        return Some(desktop.upcast());
+    }
+    info.create_app_info(Some(name)).ok().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn docs(titles: &[&str]) -> Vec<Entry> {
+        titles
+            .iter()
+            .map(|title| {
+                let doc = RecentDoc {
+                    uri: format!("file:///home/me/{title}"),
+                    mime_type: "text/plain".to_string(),
+                    app: None,
+                };
+                Entry::recent(doc, title.to_string())
+            })
+            .collect()
+    }
+
+    fn titles(entries: &[Entry]) -> Vec<&str> {
+        entries.iter().map(|e| e.title.as_str()).collect()
+    }
+
+    #[test]
+    fn empty_pattern_keeps_most_recent_first() {
+        let docs = docs(&["notes.md", "report.odt", "budget.ods"]);
+        assert_eq!(titles(&matching(&docs, "", 2)), ["notes.md", "report.odt"]);
+    }
+
+    #[test]
+    fn pattern_ranks_fuzzy_title_matches() {
+        let docs = docs(&["notes.md", "quarterly-report.odt", "report.odt", "budget.ods"]);
+        assert_eq!(titles(&matching(&docs, "report", 10)), ["report.odt", "quarterly-report.odt"]);
+        assert!(matching(&docs, "xyz", 10).is_empty());
+    }
+
+    #[test]
+    fn queries_without_the_prefix_are_not_claimed() {
+        assert!(RecentFiles::default().search("report", &Config::default()).is_none());
+    }
+}
@@ -0,0 +1,390 @@
+use std::cell::{OnceCell, RefCell};
+use std::io::{self, Read, Write};
+use std::os::fd::AsRawFd;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+use glib::{ControlFlow, IOCondition, SourceId};
+use serde::{Deserialize, Serialize};
+use crate::config::{Config, ScriptProvider};
+use crate::entry::{Entry, ScriptAction, ScriptItem};
+
+/// Written to the script's stdin, one JSON object per line.
+#[derive(Serialize)]
+struct Request<'a> {
+    id: u64,
+    query: &'a str,
+}
+
+/// Read from the script's stdout, one JSON object per line. Echoing the
+/// request's `id` lets scout drop late replies to queries typed over since.
+#[derive(Deserialize)]
+struct Response {
+    id: Option<u64>,
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+/// A result row, also returned by Lua hooks. Items with neither `action` nor
+/// `copy` are shown as hints.
+#[derive(Deserialize)]
+pub struct Item {
+    title: String,
+    subtitle: Option<String>,
+    icon: Option<String>,
+    action: Option<Vec<String>>,
+    copy: Option<String>,
+}
+
+/// Called when a script answers, so the list can be shown again.
+type Notify = Rc<RefCell<Option<Box<dyn Fn()>>>>;
+
+/// Providers implemented by external programs (`[[script_providers]]`). Each
+/// script is started on its first query and kept running for later ones.
+/// Queries are sent once typing pauses and answered asynchronously, so a slow
+/// script never blocks the window; until it replies, its previous rows stay.
+#[derive(Default)]
+pub struct Scripts {
+    scripts: OnceCell<Vec<Rc<Script>>>,
+    notify: Notify,
+}
+
+impl Scripts {
+    pub fn search(&self, query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+        let scripts = self.scripts.get_or_init(|| {
+            cfg.script_providers
+                .iter()
+                .map(|config| Rc::new(Script { config: config.clone(), notify: self.notify.clone(), state: RefCell::default() }))
+                .collect()
+        });
+        let (script, pattern) = scripts
+            .iter()
+            .find_map(|script| Some((script, query.strip_prefix(&script.config.prefix)?)))?;
+
+        let mut rows = script.search(pattern.trim());
+        rows.truncate(cfg.max_results);
+        Some(rows)
+    }
+
+    /// Sets what to do when a script's answer arrives.
+    pub fn on_update(&self, notify: impl Fn() + 'static) {
+        *self.notify.borrow_mut() = Some(Box::new(notify));
+    }
+}
+
+struct Script {
+    config: ScriptProvider,
+    notify: Notify,
+    state: RefCell<State>,
+}
+
+#[derive(Default)]
+struct State {
+    process: Option<ScriptProcess>,
+    /// Watches the process's stdout on the main loop.
+    watch: Option<SourceId>,
+    next_id: u64,
+    /// The query last sent and not answered yet.
+    pending: Option<(u64, String)>,
+    /// The query last answered and its rows.
+    answered: Option<(String, Vec<Entry>)>,
+    debounce: Option<SourceId>,
+    timeout: Option<SourceId>,
+}
+
+impl Script {
+    /// Rows for `pattern` if the script already answered it, else the
+    /// previous rows while the query is sent.
+    fn search(self: &Rc<Self>, pattern: &str) -> Vec<Entry> {
+        let mut state = self.state.borrow_mut();
+        match &state.answered {
+            Some((query, rows)) if query == pattern => return rows.clone(),
+            _ => {}
+        }
+        let shown = state.answered.as_ref().map(|(_, rows)| rows.clone()).unwrap_or_default();
+        if state.pending.as_ref().is_some_and(|(_, query)| query == pattern) {
+            return shown;
+        }
+
+        if let Some(debounce) = state.debounce.take() {
+            debounce.remove();
+        }
+        let script = Rc::downgrade(self);
+        let query = pattern.to_string();
+        state.debounce = Some(glib::timeout_add_local_once(Duration::from_millis(self.config.debounce_ms), move || {
+            if let Some(script) = script.upgrade() {
+                script.state.borrow_mut().debounce = None;
+                script.send(&query);
+            }
+        }));
+        shown
+    }
+
+    /// Sends `query`, starting the script first if it isn't running.
+    fn send(self: &Rc<Self>, query: &str) {
+        let mut state = self.state.borrow_mut();
+        if state.process.is_none() {
+            match ScriptProcess::spawn(&self.config.command) {
+                Ok(process) => {
+                    state.watch = Some(watch(Rc::downgrade(self), &process));
+                    state.process = Some(process);
+                }
+                Err(e) => {
+                    drop(state);
+                    self.answer(query, format!("Failed to start '{}': {}", self.config.command, e));
+                    return;
+                }
+            }
+        }
+
+        state.next_id += 1;
+        let id = state.next_id;
+        if let Some(process) = state.process.as_mut()
+            && let Err(e) = process.send(id, query)
+        {
+            stop(&mut state);
+            drop(state);
+            self.answer(query, format!("Failed to send query to '{}': {}", self.config.command, e));
+            return;
+        }
+        state.pending = Some((id, query.to_string()));
+
+        if let Some(timeout) = state.timeout.take() {
+            timeout.remove();
+        }
+        let script = Rc::downgrade(self);
+        state.timeout = Some(glib::timeout_add_local_once(Duration::from_millis(self.config.timeout_ms), move || {
+            if let Some(script) = script.upgrade() {
+                script.timed_out(id);
+            }
+        }));
+    }
+
+    /// Handles whatever the script wrote since last time.
+    fn read(&self) -> ControlFlow {
+        let mut state = self.state.borrow_mut();
+        let Some(process) = state.process.as_mut() else { return ControlFlow::Break };
+        let lines = process.read_lines();
+        let closed = process.closed;
+
+        let mut answered = false;
+        for line in lines.iter().filter(|line| !line.is_empty()) {
+            let Ok(response) = serde_json::from_str::<Response>(line) else {
+                eprintln!("Ignoring malformed reply from '{}': {}", self.config.command, line);
+                continue;
+            };
+            let Some((id, query)) = state.pending.clone() else { continue };
+            if response.id.is_some_and(|reply_to| reply_to != id) {
+                continue;
+            }
+            state.answered = Some((query, response.items.into_iter().map(item_entry).collect()));
+            state.pending = None;
+            if let Some(timeout) = state.timeout.take() {
+                timeout.remove();
+            }
+            answered = true;
+        }
+
+        if closed {
+            // Returning `Break` below removes the watch.
+            state.watch = None;
+            stop(&mut state);
+        }
+        let unanswered = if closed { state.pending.take() } else { None };
+        drop(state);
+
+        if let Some((_, query)) = unanswered {
+            self.answer(&query, format!("'{}' exited", self.config.command));
+        } else if answered {
+            self.notify();
+        }
+        if closed { ControlFlow::Break } else { ControlFlow::Continue }
+    }
+
+    /// Gives up on a query the script took too long to answer; the script is
+    /// restarted for the next one.
+    fn timed_out(&self, id: u64) {
+        let mut state = self.state.borrow_mut();
+        state.timeout = None;
+        let Some((_, query)) = state.pending.take_if(|(pending, _)| *pending == id) else { return };
+        stop(&mut state);
+        drop(state);
+        self.answer(&query, format!("'{}' didn't answer in time", self.config.command));
+    }
+
+    /// Shows `message` as the answer to `query`.
+    fn answer(&self, query: &str, message: String) {
+        eprintln!("{message}");
+        {
+            let mut state = self.state.borrow_mut();
+            if let Some(timeout) = state.timeout.take() {
+                timeout.remove();
+            }
+            state.answered = Some((query.to_string(), vec![Entry::hint(message)]));
+        }
+        self.notify();
+    }
+
+    fn notify(&self) {
+        if let Some(notify) = self.notify.borrow().as_ref() {
+            notify();
+        }
+    }
+}
+
+/// Calls `read` whenever the script's stdout is readable or closed.
+fn watch(script: Weak<Script>, process: &ScriptProcess) -> SourceId {
+    let condition = IOCondition::IN | IOCondition::HUP | IOCondition::ERR;
+    glib::unix_fd_add_local(process.stdout.as_raw_fd(), condition, move |_, _| match script.upgrade() {
+        Some(script) => script.read(),
+        None => ControlFlow::Break,
+    })
+}
+
+/// Ends the script and stops watching it.
+fn stop(state: &mut State) {
+    if let Some(watch) = state.watch.take() {
+        watch.remove();
+    }
+    state.process = None;
+}
+
+pub fn item_entry(item: Item) -> Entry {
+    let action = match (item.action, item.copy) {
+        (Some(argv), _) if !argv.is_empty() => Some(ScriptAction::Run(argv)),
+        (_, Some(text)) => Some(ScriptAction::Copy(text)),
+        _ => None,
+    };
+    let entry = match action {
+        Some(action) => Entry::script(ScriptItem { icon: item.icon, action }, item.title),
+        None => Entry::hint(item.title),
+    };
+    match item.subtitle {
+        Some(subtitle) => entry.with_subtitle(subtitle),
+        None => entry,
+    }
+}
+
+/// A running script with pipes to its stdin and (non-blocking) stdout.
+struct ScriptProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: ChildStdout,
+    buffer: Vec<u8>,
+    /// The script closed its stdout, most likely by exiting.
+    closed: bool,
+}
+
+impl ScriptProcess {
+    fn spawn(command: &str) -> io::Result<Self> {
+        let mut child = Command::new("/bin/sh")
+            .args(["-c", command])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(io::Error::other("no pipes to the script"));
+        };
+        let fd = stdout.as_raw_fd();
+        if unsafe { libc::fcntl(fd, libc::F_SETFL, libc::fcntl(fd, libc::F_GETFL) | libc::O_NONBLOCK) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self { child, stdin, stdout, buffer: Vec::new(), closed: false })
+    }
+
+    fn send(&mut self, id: u64, query: &str) -> io::Result<()> {
+        let mut line = serde_json::to_string(&Request { id, query })?;
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes())?;
+        self.stdin.flush()
+    }
+
+    /// The complete lines available without blocking.
+    fn read_lines(&mut self) -> Vec<String> {
+        let mut chunk = [0; 8192];
+        loop {
+            match self.stdout.read(&mut chunk) {
+                Ok(0) => {
+                    self.closed = true;
+                    break;
+                }
+                Ok(n) => self.buffer.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(_) => {
+                    self.closed = true;
+                    break;
+                }
+            }
+        }
+
+        let mut lines = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            lines.push(String::from_utf8_lossy(&line).trim().to_string());
+        }
+        lines
+    }
+}
+
+impl Drop for ScriptProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::EntryKind;
+
+    #[test]
+    fn items_become_entries_by_action() {
+        let response: Response = serde_json::from_str(
+            r#"{"id": 1, "items": [
+                {"title": "Open docs", "icon": "help-browser", "action": ["xdg-open", "https://docs.rs"]},
+                {"title": "token", "subtitle": "GitHub", "copy": "ghp_123"},
+                {"title": "No matches"}
+            ]}"#,
+        )
+        .unwrap();
+        let entries: Vec<Entry> = response.items.into_iter().map(item_entry).collect();
+
+        assert!(matches!(
+            &entries[0].kind,
+            EntryKind::Script(ScriptItem { icon: Some(icon), action: ScriptAction::Run(argv) })
+                if icon == "help-browser" && argv[0] == "xdg-open"
+        ));
+        assert_eq!(entries[1].subtitle.as_deref(), Some("GitHub"));
+        assert!(matches!(&entries[1].kind, EntryKind::Script(item) if item.action == ScriptAction::Copy("ghp_123".to_string())));
+        assert!(matches!(entries[2].kind, EntryKind::Hint));
+    }
+
+    /// The script stays up between queries; replies are read without blocking.
+    #[test]
+    fn process_is_reused_across_queries() {
+        let echo_id = r#"while read -r line; do id=${line#*\"id\":}; echo "{\"id\":${id%%,*},\"items\":[{\"title\":\"$$\"}]}"; done"#;
+        let mut process = ScriptProcess::spawn(echo_id).unwrap();
+        assert!(process.read_lines().is_empty());
+
+        let mut replies = Vec::new();
+        for id in 1..=2 {
+            process.send(id, "fo\"o").unwrap();
+            let mut lines = Vec::new();
+            for _ in 0..200 {
+                lines = process.read_lines();
+                if !lines.is_empty() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            let response: Response = serde_json::from_str(&lines[0]).unwrap();
+            assert_eq!(response.id, Some(id));
+            replies.push(response.items[0].title.clone());
+        }
+        // Both answers came from the same shell.
+        assert_eq!(replies[0], replies[1]);
+        assert!(!process.closed);
+    }
+}
@@ -0,0 +1,255 @@
+use std::cell::OnceCell;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::config::Config;
+use crate::entry::{Entry, SshHost};
+use crate::glob::glob_match;
+use crate::history;
+use crate::providers::files::expand_tilde;
+use crate::search::fuzzy_scores;
+
+/// SSH hosts, e.g. `ssh build`.
+pub const PREFIX: &str = "ssh ";
+
+/// Nested `Include` directives deeper than this are ignored, like ssh does.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Score bonus for the most recently used host; each older one gets a bit less.
+const RECENT_BONUS: i64 = 100;
+
+/// Hosts from `~/.ssh/config` (following `Include`) and `~/.ssh/known_hosts`,
+/// opened as `ssh <host>` in the configured terminal.
+#[derive(Default)]
+pub struct SshHosts {
+    hosts: OnceCell<Vec<SshHost>>,
+    recent: OnceCell<Vec<String>>,
+}
+
+impl SshHosts {
+    pub fn search(&self, query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+        let pattern = query.strip_prefix(PREFIX)?.trim();
+        let hosts = self.hosts.get_or_init(|| load(&expand_tilde("~/.ssh")));
+        let recent = self.recent.get_or_init(|| history::load(history::SSH_HOSTS));
+
+        let bonus = |host: &SshHost| {
+            recent
+                .iter()
+                .position(|key| *key == host.to_string())
+                .map_or(0, |i| (RECENT_BONUS - 10 * i as i64).max(0))
+        };
+
+        let mut scored: Vec<(i64, &SshHost)> = if pattern.is_empty() {
+            hosts.iter().map(|host| (0, host)).collect()
+        } else {
+            fuzzy_scores(hosts.iter(), pattern, |host| &host.host)
+        };
+        for (score, host) in scored.iter_mut() {
+            *score += bonus(host);
+        }
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+        Some(
+            scored
+                .into_iter()
+                .take(cfg.max_results)
+                .map(|(_, host)| Entry::ssh(host.clone()))
+                .collect(),
+        )
+    }
+}
+
+/// Config hosts first, in file order, then any extra names from `known_hosts`.
+fn load(ssh_dir: &Path) -> Vec<SshHost> {
+    let mut names = Vec::new();
+    parse_config(&ssh_dir.join("config"), ssh_dir, 0, &mut names);
+
+    let mut hosts: Vec<SshHost> = names.into_iter().map(|host| SshHost { host, port: None }).collect();
+    if let Ok(raw) = fs::read_to_string(ssh_dir.join("known_hosts")) {
+        hosts.extend(parse_known_hosts(&raw));
+    }
+
+    // A name like `-oProxyCommand=…` would be read as an option, not a host.
+    let mut seen = HashSet::new();
+    hosts.retain(|host| !host.host.starts_with('-') && seen.insert(host.to_string()));
+    hosts
+}
+
+/// Collects concrete `Host` aliases. Patterns with wildcards or negations
+/// can't be connected to directly and are skipped.
+fn parse_config(path: &Path, ssh_dir: &Path, depth: usize, out: &mut Vec<String>) {
+    if depth > MAX_INCLUDE_DEPTH {
+        return;
+    }
+    let Ok(raw) = fs::read_to_string(path) else { return };
+
+    for line in raw.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // Keywords are case-insensitive and may be separated by `=`.
+        let (keyword, args) = line
+            .split_once(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or((line, ""));
+        let args = args.trim_start_matches(|c: char| c.is_whitespace() || c == '=');
+
+        match keyword.to_ascii_lowercase().as_str() {
+            "host" => {
+                for name in args.split_whitespace().map(|a| a.trim_matches('"')) {
+                    if !name.contains(['*', '?', '!']) && !out.iter().any(|known| known == name) {
+                        out.push(name.to_string());
+                    }
+                }
+            }
+            "include" => {
+                for pattern in args.split_whitespace().map(|a| a.trim_matches('"')) {
+                    for included in resolve_include(pattern, ssh_dir) {
+                        parse_config(&included, ssh_dir, depth + 1, out);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Relative includes are resolved against `~/.ssh`; wildcards are allowed in
+/// the file name, e.g. `Include config.d/*`.
+fn resolve_include(pattern: &str, ssh_dir: &Path) -> Vec<PathBuf> {
+    let path = if pattern.starts_with('~') {
+        expand_tilde(pattern)
+    } else {
+        ssh_dir.join(pattern)
+    };
+
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    if !name.contains(['*', '?']) {
+        return vec![path];
+    }
+
+    let Some(dir) = path.parent() else { return Vec::new() };
+    let Ok(read_dir) = fs::read_dir(dir) else { return Vec::new() };
+    let mut matches: Vec<PathBuf> = read_dir
+        .flatten()
+        .filter(|dirent| glob_match(&name, &dirent.file_name().to_string_lossy()))
+        .map(|dirent| dirent.path())
+        .collect();
+    matches.sort();
+    matches
+}
+
+/// Reads plain host names from `known_hosts`. Hashed entries (`|1|…`) can't be
+/// reversed and marker lines describe CAs or revocations, so both are skipped.
+fn parse_known_hosts(raw: &str) -> Vec<SshHost> {
+    let mut hosts = Vec::new();
+
+    for line in raw.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('@') {
+            continue;
+        }
+        let Some(field) = line.split_whitespace().next() else { continue };
+
+        for name in field.split(',') {
+            if name.starts_with('|') || name.contains(['*', '?', '!']) {
+                continue;
+            }
+            if let Some(host) = parse_host_port(name) {
+                hosts.push(host);
+            }
+        }
+    }
+
+    hosts
+}
+
+/// `host` or `[host]:port`; port 22 is dropped since it's the default.
+fn parse_host_port(name: &str) -> Option<SshHost> {
+    let Some(rest) = name.strip_prefix('[') else {
+        return Some(SshHost { host: name.to_string(), port: None });
+    };
+    let (host, port) = rest.split_once("]:")?;
+    let port: u16 = port.parse().ok()?;
+    Some(SshHost {
+        host: host.to_string(),
+        port: (port != 22).then_some(port),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_ssh_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("config.d")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn config_follows_includes_and_skips_patterns() {
+        let tmp = temp_ssh_dir();
+        let dir = tmp.path();
+        fs::write(
+            dir.join("config"),
+            "Include config.d/*\nHost build build.internal *.corp !bastion\n  User me\nHost=staging\n",
+        )
+        .unwrap();
+        fs::write(dir.join("config.d/work"), "Host bastion\nHost   db-?\nInclude config\n").unwrap();
+
+        let mut names = Vec::new();
+        parse_config(&dir.join("config"), dir, 0, &mut names);
+        assert_eq!(names, vec!["bastion", "build", "build.internal", "staging"]);
+    }
+
+    #[test]
+    fn known_hosts_skips_hashed_entries() {
+        let raw = "\
+github.com,140.82.121.4 ssh-ed25519 AAAA
+|1|F1E1KeoE/eEWhi10WpGv4OdiO6Y=|3988QV0VE8wmZL7suNrYQLITLCg= ssh-rsa AAAA
+[git.example.org]:2222 ssh-ed25519 AAAA
+[plain.example.org]:22 ssh-ed25519 AAAA
+@cert-authority *.example.org ssh-rsa AAAA
+";
+        let hosts: Vec<String> = parse_known_hosts(raw).iter().map(|h| h.to_string()).collect();
+        assert_eq!(hosts, vec!["github.com", "140.82.121.4", "git.example.org:2222", "plain.example.org"]);
+    }
+
+    #[test]
+    fn load_deduplicates_config_and_known_hosts() {
+        let tmp = temp_ssh_dir();
+        let dir = tmp.path();
+        fs::write(dir.join("config"), "Host github.com\n").unwrap();
+        fs::write(
+            dir.join("known_hosts"),
+            "github.com ssh-ed25519 AAAA\nother ssh-ed25519 AAAA\n-oProxyCommand=touch ssh-ed25519 AAAA\n",
+        )
+        .unwrap();
+
+        let hosts: Vec<String> = load(dir).iter().map(|h| h.to_string()).collect();
+        assert_eq!(hosts, vec!["github.com", "other"]);
+    }
+
+    #[test]
+    fn recently_used_hosts_rank_higher() {
+        let provider = SshHosts::default();
+        let hosts = ["alpha", "beta", "gamma"]
+            .iter()
+            .map(|h| SshHost { host: h.to_string(), port: None })
+            .collect();
+        let _ = provider.hosts.set(hosts);
+        let _ = provider.recent.set(vec!["gamma".to_string()]);
+
+        let results = provider.search("ssh ", &Config::default()).unwrap();
+        assert_eq!(results[0].title, "ssh gamma");
+    }
+
+    #[test]
+    fn argv_ends_options_before_the_host() {
+        let host = SshHost { host: "git.example.org".to_string(), port: Some(2222) };
+        assert_eq!(host.argv(), ["ssh", "-p", "2222", "--", "git.example.org"]);
+        assert_eq!(host.command_line(), "ssh -p 2222 git.example.org");
+    }
+}
@@ -0,0 +1,142 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use crate::config::Config;
+use crate::entry::{Entry, Unit, UnitAction};
+use crate::search::fuzzy_rank;
+use crate::systemd::Systemd;
+
+/// systemd user units, e.g. `sd syncthing`.
+pub const PREFIX: &str = "sd ";
+
+/// User units from the service manager, listed in the background once per
+/// window. Enter offers start/stop/restart/logs, Shift+Enter opens the logs.
+#[derive(Default)]
+pub struct Units {
+    units: Rc<RefCell<Option<Vec<Unit>>>>,
+    listing: Cell<bool>,
+    notify: RefCell<Option<Rc<dyn Fn()>>>,
+}
+
+impl Units {
+    pub fn search(&self, query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+        let pattern = query.strip_prefix(PREFIX)?.trim();
+        self.ensure_listing();
+        let units = self.units.borrow();
+        let Some(units) = units.as_ref() else { return Some(vec![Entry::hint("Listing units…".to_string())]) };
+
+        let found: Vec<&Unit> = if pattern.is_empty() {
+            units.iter().take(cfg.max_results).collect()
+        } else {
+            let keyed: Vec<(String, &Unit)> = units
+                .iter()
+                .map(|unit| (format!("{} {}", unit.name, unit.description), unit))
+                .collect();
+            fuzzy_rank(keyed.iter(), pattern, cfg.max_results, |(key, _)| key)
+                .into_iter()
+                .map(|(_, unit)| *unit)
+                .collect()
+        };
+        Some(found.into_iter().map(|unit| entry(unit.clone())).collect())
+    }
+
+    /// Sets what to do once the units are listed.
+    pub fn on_update(&self, notify: Rc<dyn Fn()>) {
+        *self.notify.borrow_mut() = Some(notify);
+    }
+
+    /// Asks the manager for its units on first use, without blocking the window.
+    fn ensure_listing(&self) {
+        if self.listing.replace(true) {
+            return;
+        }
+        let units = self.units.clone();
+        let notify = self.notify.borrow().clone();
+        glib::MainContext::default().spawn_local(async move {
+            *units.borrow_mut() = Some(list().await);
+            if let Some(notify) = notify {
+                notify();
+            }
+        });
+    }
+}
+
+/// The rows shown when a unit is picked: only the jobs that make sense in
+/// its current state, then its logs.
+pub fn actions(unit: &Unit) -> Vec<Entry> {
+    let jobs: &[UnitAction] = if is_running(unit) {
+        &[UnitAction::Restart, UnitAction::Stop]
+    } else {
+        &[UnitAction::Start]
+    };
+    jobs.iter()
+        .chain(&[UnitAction::Logs])
+        .map(|&action| {
+            let title = format!("{} {}", verb(action), unit.name);
+            Entry::unit_action(unit.clone(), action, title)
+        })
+        .chain([Entry::cancel()])
+        .collect()
+}
+
+/// Follows the unit's journal, for running in a terminal.
+pub fn logs_argv(unit: &Unit) -> Vec<String> {
+    ["journalctl", "--user", "-f", "-u", &unit.name].map(str::to_string).to_vec()
+}
+
+fn verb(action: UnitAction) -> &'static str {
+    match action {
+        UnitAction::Start => "Start",
+        UnitAction::Stop => "Stop",
+        UnitAction::Restart => "Restart",
+        UnitAction::Logs => "Show logs of",
+    }
+}
+
+fn is_running(unit: &Unit) -> bool {
+    matches!(unit.active_state.as_str(), "active" | "activating" | "reloading")
+}
+
+fn entry(unit: Unit) -> Entry {
+    let subtitle = format!("{} ({}) · {}", unit.active_state, unit.sub_state, unit.description);
+    Entry::unit(unit).with_subtitle(subtitle)
+}
+
+/// Units with running ones first, or none when there is no session bus.
+async fn list() -> Vec<Unit> {
+    let units = match Systemd::user() {
+        Ok(systemd) => systemd.units().await,
+        Err(e) => Err(e),
+    };
+    match units {
+        Ok(mut units) => {
+            units.sort_by(|a, b| is_running(b).cmp(&is_running(a)).then_with(|| a.name.cmp(&b.name)));
+            units
+        }
+        Err(e) => {
+            eprintln!("Failed to list systemd user units: {e}");
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn actions_depend_on_active_state() {
+        let mut unit = Unit {
+            name: "syncthing.service".to_string(),
+            description: "Syncthing".to_string(),
+            active_state: "active".to_string(),
+            sub_state: "running".to_string(),
+        };
+        let titles = |unit: &Unit| actions(unit).into_iter().map(|e| e.title).collect::<Vec<_>>();
+        assert_eq!(
+            titles(&unit),
+            ["Restart syncthing.service", "Stop syncthing.service", "Show logs of syncthing.service", "Cancel"]
+        );
+        unit.active_state = "failed".to_string();
+        assert_eq!(titles(&unit), ["Start syncthing.service", "Show logs of syncthing.service", "Cancel"]);
+    }
+}
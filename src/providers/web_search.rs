@@ -0,0 +1,68 @@
+use crate::config::{Config, SearchEngine};
+use crate::entry::Entry;
+
+/// Claims `<keyword> <terms>` when the keyword names a configured engine,
+/// e.g. `rs tokio::select`.
+pub fn search(query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+    let (keyword, terms) = query.trim_start().split_once(char::is_whitespace)?;
+    let terms = terms.trim();
+    if terms.is_empty() {
+        return None;
+    }
+
+    let engine = cfg.search_engines.iter().find(|e| e.keyword == keyword)?;
+    Some(vec![entry(engine, terms)])
+}
+
+/// A row searching the first configured engine for the whole query, shown
+/// when nothing local matched.
+pub fn fallback(query: &str, cfg: &Config) -> Option<Entry> {
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+    cfg.search_engines.first().map(|engine| entry(engine, query))
+}
+
+fn entry(engine: &SearchEngine, terms: &str) -> Entry {
+    let url = search_url(engine, terms);
+    Entry::url(url.clone(), format!("Search {} for \u{201c}{}\u{201d}", engine.name, terms)).with_subtitle(url)
+}
+
+fn search_url(engine: &SearchEngine, terms: &str) -> String {
+    let encoded = glib::Uri::escape_string(terms, None, false);
+    engine.url.replace("{query}", &encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyword_builds_encoded_url() {
+        let cfg = Config::default();
+        let results = search("rs tokio::select", &cfg).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].subtitle.as_deref(),
+            Some("https://docs.rs/releases/search?query=tokio%3A%3Aselect")
+        );
+    }
+
+    #[test]
+    fn unknown_keyword_or_missing_terms_are_not_claimed() {
+        let cfg = Config::default();
+        assert!(search("firefox private", &cfg).is_none());
+        assert!(search("g ", &cfg).is_none());
+    }
+
+    #[test]
+    fn fallback_uses_first_engine() {
+        let cfg = Config::default();
+        let entry = fallback("rust borrow checker", &cfg).unwrap();
+        assert_eq!(
+            entry.subtitle.as_deref(),
+            Some("https://www.google.com/search?q=rust%20borrow%20checker")
+        );
+    }
+}
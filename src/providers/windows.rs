@@ -0,0 +1,217 @@
+use std::cell::OnceCell;
+use std::path::{Path, PathBuf};
+use gio::prelude::AppInfoExt;
+use gio::AppInfo;
+use glib::Cast;
+use crate::config::Config;
+use crate::entry::{Entry, EntryKind, OpenWindow};
+use crate::ewmh::Ewmh;
+use crate::launcher;
+use crate::procfs;
+use crate::search::fuzzy_rank;
+
+/// Open windows, e.g. `w mail`.
+pub const PREFIX: &str = "w ";
+
+/// Top-level windows from the window manager, listed once per search session.
+/// Scout's own window is left out. Icons are only read for `w` queries since
+/// they can be large.
+#[derive(Default)]
+pub struct Windows {
+    windows: OnceCell<Vec<OpenWindow>>,
+    running: OnceCell<Vec<OpenWindow>>,
+}
+
+impl Windows {
+    pub fn search(&self, query: &str, cfg: &Config) -> Option<Vec<Entry>> {
+        let pattern = query.strip_prefix(PREFIX)?.trim();
+        let windows = self.windows.get_or_init(|| list(Some(cfg.icon_size.max(1) as u32)));
+
+        let found: Vec<&OpenWindow> = if pattern.is_empty() {
+            windows.iter().take(cfg.max_results).collect()
+        } else {
+            let keyed: Vec<(String, &OpenWindow)> = windows
+                .iter()
+                .map(|window| (format!("{} {}", window.class, window.title), window))
+                .collect();
+            fuzzy_rank(keyed.iter(), pattern, cfg.max_results, |(key, _)| key)
+                .into_iter()
+                .map(|(_, window)| *window)
+                .collect()
+        };
+        Some(found.into_iter().map(|window| entry(window.clone())).collect())
+    }
+
+    /// Marks applications that already have a window and says which key
+    /// switches to it.
+    pub fn annotate(&self, entries: &mut [Entry], cfg: &Config) {
+        let windows = self.running.get_or_init(|| list(None));
+        if windows.is_empty() {
+            return;
+        }
+        for entry in entries.iter_mut() {
+            if let EntryKind::App(app) = &entry.kind
+                && entry.subtitle.is_none()
+                && find_for_app(app, windows).is_some()
+            {
+                let hint = if cfg.focus_existing_for(app.id().as_deref()) {
+                    "Running · Shift+Enter for a new instance"
+                } else {
+                    "Running · Shift+Enter to switch to it"
+                };
+                entry.subtitle = Some(hint.to_string());
+            }
+        }
+    }
+}
+
+/// Open windows, or none when there is no X display to ask.
+pub fn list(icon_size: Option<u32>) -> Vec<OpenWindow> {
+    let result = Ewmh::connect().and_then(|ewmh| ewmh.windows(icon_size));
+    match result {
+        Ok(mut windows) => {
+            windows.retain(|window| window.pid != Some(std::process::id()));
+            windows
+        }
+        Err(e) => {
+            if std::env::var_os("DISPLAY").is_some() {
+                eprintln!("Failed to list windows: {e}");
+            }
+            Vec::new()
+        }
+    }
+}
+
+/// The first window belonging to `app`.
+pub fn find_for_app<'a>(app: &AppInfo, windows: &'a [OpenWindow]) -> Option<&'a OpenWindow> {
+    let keys = app_keys(app);
+    windows.iter().find(|window| window_matches(window, &keys))
+}
+
+/// A window of `app` matched by `WM_CLASS`, or else one owned by its process.
+pub fn find_running(app: &AppInfo) -> Option<OpenWindow> {
+    let windows = list(None);
+    if let Some(window) = find_for_app(app, &windows) {
+        return Some(window.clone());
+    }
+    find_by_process(app, windows, Path::new(procfs::PROC_ROOT))
+}
+
+/// A window owned by the process GIO started from `app`'s desktop file, or
+/// from the copy made to pass it extra `args`, or by one running the app's
+/// executable when that names the app itself.
+pub fn find_by_process(app: &AppInfo, windows: Vec<OpenWindow>, proc_root: &Path) -> Option<OpenWindow> {
+    let desktop_files: Vec<PathBuf> = app
+        .downcast_ref::<gio::DesktopAppInfo>()
+        .and_then(|dai| dai.filename())
+        .map(|file| launcher::launch_copy_path(&file).into_iter().chain([file]).collect())
+        .unwrap_or_default();
+    let pids = exe_name(app).map(|name| procfs::pids_running(proc_root, &name)).unwrap_or_default();
+    windows.into_iter().find(|window| {
+        window.pid.is_some_and(|pid| {
+            pids.contains(&pid)
+                || procfs::launched_desktop_file(proc_root, pid).is_some_and(|file| desktop_files.contains(&file))
+        })
+    })
+}
+
+pub fn activate(window: &OpenWindow) -> Result<(), String> {
+    Ewmh::connect()
+        .and_then(|ewmh| ewmh.activate(window.id))
+        .map_err(|e| format!("Failed to switch to '{}': {}", window.title, e))
+}
+
+fn entry(window: OpenWindow) -> Entry {
+    let subtitle = window.class.clone();
+    Entry::window(window).with_subtitle(subtitle)
+}
+
+/// Names a window of `app` may carry in `WM_CLASS`: the desktop file's
+/// `StartupWMClass`, its id without `.desktop`, and the executable name.
+fn app_keys(app: &AppInfo) -> Vec<String> {
+    let mut keys = Vec::new();
+    if let Some(dai) = app.downcast_ref::<gio::DesktopAppInfo>()
+        && let Some(class) = dai.startup_wm_class()
+    {
+        keys.push(class.to_string());
+    }
+    if let Some(id) = app.id() {
+        keys.push(id.trim_end_matches(".desktop").to_string());
+    }
+    keys.extend(exe_name(app));
+    keys
+}
+
+/// Programs that run other programs: their name says nothing about which app
+/// a process or window belongs to.
+const WRAPPERS: &[&str] = &[
+    "env", "sh", "bash", "dash", "zsh", "flatpak", "snap", "firejail", "bwrap", "nice", "ionice", "taskset",
+    "gamemoderun", "prime-run", "sudo", "pkexec", "python", "perl", "ruby", "node", "java", "mono", "gjs", "wine",
+];
+
+/// The file name of `app`'s executable, unless it is a wrapper or
+/// interpreter. Versioned names such as `python3.12` count as the wrapper.
+fn exe_name(app: &AppInfo) -> Option<String> {
+    let exe = app.executable();
+    let name = exe.file_name()?.to_string_lossy().into_owned();
+    let unversioned = name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    (!WRAPPERS.contains(&unversioned)).then_some(name)
+}
+
+fn window_matches(window: &OpenWindow, keys: &[String]) -> bool {
+    keys.iter().any(|key| {
+        !key.is_empty() && (key.eq_ignore_ascii_case(&window.class) || key.eq_ignore_ascii_case(&window.instance))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn window(instance: &str, class: &str) -> OpenWindow {
+        OpenWindow {
+            id: 1,
+            title: String::new(),
+            instance: instance.to_string(),
+            class: class.to_string(),
+            pid: None,
+            icon: None,
+        }
+    }
+
+    fn app(commandline: &str) -> AppInfo {
+        AppInfo::create_from_commandline(commandline, None, gio::AppInfoCreateFlags::NONE).unwrap()
+    }
+
+    #[test]
+    fn wrappers_and_interpreters_are_not_matched_by_name() {
+        assert_eq!(exe_name(&app("/usr/bin/kitty --single-instance")).as_deref(), Some("kitty"));
+        assert_eq!(exe_name(&app("flatpak run org.gimp.GIMP")), None);
+        assert_eq!(exe_name(&app("/usr/bin/python3.12 /opt/tool/main.py")), None);
+        assert_eq!(exe_name(&app("env GDK_BACKEND=x11 slack")), None);
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("30")).unwrap();
+        fs::write(root.join("30/cmdline"), b"python3\0/opt/other/app.py\0").unwrap();
+        fs::create_dir_all(root.join("31")).unwrap();
+        fs::write(root.join("31/cmdline"), b"/usr/bin/kitty\0").unwrap();
+        let mut editor = window("editor", "Editor");
+        editor.pid = Some(30);
+        let mut terminal = window("term", "Term");
+        terminal.pid = Some(31);
+
+        assert!(find_by_process(&app("python3 /opt/tool/main.py"), vec![editor], root).is_none());
+        assert_eq!(find_by_process(&app("kitty"), vec![terminal], root).and_then(|w| w.pid), Some(31));
+    }
+
+    #[test]
+    fn matches_wm_class_case_insensitively() {
+        let keys = vec!["org.gnome.Nautilus".to_string(), "nautilus".to_string()];
+        assert!(window_matches(&window("org.gnome.Nautilus", "Org.gnome.Nautilus"), &keys));
+        assert!(window_matches(&window("nautilus", "Nautilus"), &keys));
+        assert!(!window_matches(&window("kitty", "kitty"), &keys));
+        assert!(!window_matches(&window("", ""), &[String::new()]));
+    }
+}
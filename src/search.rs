@@ -1,72 +1,259 @@
+use std::cmp::Reverse;
 use std::rc::Rc;
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
-use gio::AppInfo;
+use gio::{AppInfo, DesktopAppInfo};
 use gio::prelude::AppInfoExt;
+use glib::Cast;
 use crate::config::Config;
-use crate::entry::{Entry, SystemAction};
+use meval::{FuncEvalError, ParseError, RPNError};
+use crate::entry::{Entry, EntryKind, SystemAction};
+use crate::providers::executables;
+use crate::providers::files::{complete_path, looks_like_path};
 
-pub fn get_entries() -> Vec<Entry> {
+pub fn get_entries(cfg: &Config) -> Vec<Entry> {
     let mut entries: Vec<Entry> = AppInfo::all()
         .into_iter()
         .filter(|a| a.should_show())
         .map(Entry::from_app)
         .collect();
 
-    entries.push(Entry::system_action(SystemAction::Shutdown));
-    entries.push(Entry::system_action(SystemAction::Restart));
-    entries.push(Entry::system_action(SystemAction::Sleep));
-    entries.push(Entry::system_action(SystemAction::Hibernate));
+    if cfg.path_executables {
+        let executables = executables::entries(&entries);
+        entries.extend(executables);
+    }
+    entries.retain(|entry| !is_hidden(entry, cfg));
+
+    if cfg.show_system_actions {
+        entries.push(Entry::system_action(SystemAction::Shutdown));
+        entries.push(Entry::system_action(SystemAction::Restart));
+        entries.push(Entry::system_action(SystemAction::Sleep));
+        entries.push(Entry::system_action(SystemAction::Hibernate));
+    }
 
     entries
 }
 
+/// Whether `hidden_apps` or `hidden_categories` leaves `entry` out.
+fn is_hidden(entry: &Entry, cfg: &Config) -> bool {
+    if entry.app_id().is_some_and(|id| cfg.hides_app(&id)) {
+        return true;
+    }
+    match &entry.kind {
+        EntryKind::App(app) => app
+            .downcast_ref::<DesktopAppInfo>()
+            .and_then(|desktop| desktop.categories())
+            .is_some_and(|categories| cfg.hides_categories(&categories)),
+        _ => false,
+    }
+}
+
+/// Scores every item whose key fuzzy-matches `query`, in input order.
+pub fn fuzzy_scores<T>(items: impl IntoIterator<Item = T>, query: &str, key: impl Fn(&T) -> &str) -> Vec<(i64, T)> {
+    let matcher = SkimMatcherV2::default();
+    items
+        .into_iter()
+        .filter_map(|item| matcher.fuzzy_match(key(&item), query).map(|score| (score, item)))
+        .collect()
+}
+
+/// Returns up to `limit` items whose key fuzzy-matches `query`, best first.
+pub fn fuzzy_rank<T>(items: impl IntoIterator<Item = T>, query: &str, limit: usize, key: impl Fn(&T) -> &str) -> Vec<T> {
+    let mut scored = fuzzy_scores(items, query, key);
+    scored.sort_by_key(|(score, _)| Reverse(*score));
+    scored.into_iter().take(limit).map(|(_, item)| item).collect()
+}
+
 pub fn top_matches(entries: &Rc<Vec<Entry>>, query: &str, cfg: Rc<Config>) -> Vec<Entry> {
+    top_matches_rescored(entries, query, cfg, |_, score| score)
+}
+
+/// Like `top_matches`, with `rescore` adjusting each fuzzy match's score before ranking.
+pub fn top_matches_rescored(
+    entries: &Rc<Vec<Entry>>,
+    query: &str,
+    cfg: Rc<Config>,
+    rescore: impl Fn(&Entry, i64) -> i64,
+) -> Vec<Entry> {
+    // `gimp ~/Pictures/foo.png`: offer the apps matching the name, with the files attached.
+    if let Some((name, args)) = split_launch_args(query) {
+        let launches: Vec<Entry> = ranked_matches(entries, name, &cfg, &rescore)
+            .into_iter()
+            .filter_map(|entry| match entry.kind {
+                EntryKind::App(app) => Some(Entry::app_with_args(app, args.clone(), entry.title)),
+                _ => None,
+            })
+            .collect();
+        if !launches.is_empty() {
+            return launches;
+        }
+    }
+    ranked_matches(entries, query, &cfg, &rescore)
+}
+
+fn ranked_matches(entries: &[Entry], query: &str, cfg: &Config, rescore: &dyn Fn(&Entry, i64) -> i64) -> Vec<Entry> {
     let q = query.trim();
     if q.is_empty() {
-        return entries.iter().take(cfg.max_results).cloned().collect();
+        return pinned_first(entries, &cfg.pinned).into_iter().take(cfg.max_results).cloned().collect();
     }
 
-    let matcher = SkimMatcherV2::default();
-    let mut scored: Vec<(i64, Entry)> = entries
-        .iter()
-        .filter_map(|entry| {
-            matcher
-                .fuzzy_match(&entry.title, q)
-                .map(|score| (score, entry.clone()))
-        })
+    let mut scored: Vec<(i64, Entry)> = fuzzy_scores(entries.iter(), q, |e| &e.title)
+        .into_iter()
+        .map(|(score, entry)| (rescore(entry, score), entry.clone()))
         .collect();
 
-    let math_query = meval::eval_str(query);
+    if let Some(result) = evaluate_math(q) {
+        scored.push((result.score, Entry::math_result(result.value)));
+    }
 
-    if query.ends_with('+') ||
-        query.ends_with('-') ||
-        query.ends_with('*') ||
-        query.ends_with('/') ||
-        query.ends_with('(') ||
-        query.ends_with(')') {
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
 
-        // Handle incomplete expressions by trimming the last character
-        let incomplete_expression = query[..query.len()-1].trim();
-        if let Ok(result) = meval::eval_str(incomplete_expression) {
-            Entry::math_result(result);
-            scored.push((900i64, Entry::math_result(result)));
-        }
+    // An exact alias outranks every fuzzy score.
+    let alias = cfg
+        .aliases
+        .get(q)
+        .and_then(|id| entries.iter().find(|e| e.app_id().is_some_and(|e_id| id_matches(&e_id, id))));
+    if let Some(alias) = alias {
+        scored.retain(|(_, e)| e.app_id() != alias.app_id());
+    }
+    let mut results: Vec<Entry> = alias
+        .cloned()
+        .into_iter()
+        .chain(scored.into_iter().map(|(_, e)| e))
+        .take(cfg.max_results)
+        .collect();
+
+    // Hints only fill leftover slots so they never push out a real match.
+    if results.len() < cfg.max_results
+        && let Some(hint) = math_hint(q)
+    {
+        results.push(Entry::math_hint(hint));
     }
-    // Otherwise handle complete expressions normally
-    else if let Ok(result) = math_query {
-        Entry::math_result(result);
-        scored.push((1000i64, Entry::math_result(result)));
+
+    results
+}
+
+/// Splits `code .` or `gimp ~/Pictures/foo.png` into the app name to search for
+/// and the files or URLs to open with it. Every argument has to look like a path
+/// or URL, so multi-word names such as `visual studio` are still searched whole.
+pub fn split_launch_args(query: &str) -> Option<(&str, Vec<String>)> {
+    let mut tokens = query.split_whitespace();
+    let name = tokens.next()?;
+    let args: Vec<String> = tokens.map(str::to_string).collect();
+    let all_files = args.iter().all(|arg| looks_like_path(arg) || arg.contains("://"));
+    (!args.is_empty() && all_files).then_some((name, args))
+}
+
+/// Tab completion of the path being typed at the end of `query`, whether it is
+/// an app's argument (`gimp ~/Pic`) or a directory being browsed (`~/Doc`).
+pub fn complete_query(query: &str) -> Option<String> {
+    let start = query.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+    let (head, last) = query.split_at(start);
+    if !looks_like_path(last) {
+        return None;
     }
+    Some(format!("{}{}", head, complete_path(last)?))
+}
 
-    scored.sort_by(|a, b| b.0.cmp(&a.0));
-    scored.into_iter().take(cfg.max_results).map(|(_, e)| e.clone()).collect()
+/// Entries whose id is in `pinned`, in that order, followed by the rest in their
+/// original order.
+fn pinned_first<'a>(entries: &'a [Entry], pinned: &[String]) -> Vec<&'a Entry> {
+    let pin_rank = |entry: &Entry| {
+        let id = entry.app_id()?;
+        pinned.iter().position(|pin| id_matches(&id, pin))
+    };
+    let mut ordered: Vec<(Option<usize>, &Entry)> = entries.iter().map(|e| (pin_rank(e), e)).collect();
+    // Stable, so unpinned entries keep their order; `None` sorts after every pin.
+    ordered.sort_by_key(|(rank, _)| rank.unwrap_or(usize::MAX));
+    ordered.into_iter().map(|(_, e)| e).collect()
+}
+
+/// Config may name a desktop file id with or without its `.desktop` suffix.
+fn id_matches(id: &str, key: &str) -> bool {
+    id == key || id.strip_suffix(".desktop") == Some(key)
+}
+
+struct MathResult {
+    value: f64,
+    score: i64,
+}
+
+/// Evaluates `query` as a math expression. Incomplete expressions such as `2*(3+`
+/// are completed by dropping trailing operators and closing open parentheses.
+fn evaluate_math(query: &str) -> Option<MathResult> {
+    if let Ok(value) = meval::eval_str(query) {
+        return Some(MathResult { value, score: 1000 });
+    }
+
+    let completed = complete_expression(query)?;
+    meval::eval_str(completed)
+        .ok()
+        .map(|value| MathResult { value, score: 900 })
+}
+
+fn complete_expression(query: &str) -> Option<String> {
+    let trimmed = query.trim_end_matches(|c: char| c.is_whitespace() || "+-*/^%(,".contains(c));
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let open = trimmed.matches('(').count();
+    let close = trimmed.matches(')').count();
+    let missing = open.checked_sub(close)?;
+
+    let completed = format!("{}{}", trimmed, ")".repeat(missing));
+    if completed == query { None } else { Some(completed) }
+}
+
+/// Only queries containing a number or a call, plus an operator or parenthesis,
+/// are treated as math, so plain app names never produce hints.
+fn looks_like_math(query: &str) -> bool {
+    let has_operand = query.chars().any(|c| c.is_ascii_digit() || c == '(');
+    let has_operator = query.chars().any(|c| "+-*/^%()".contains(c));
+    has_operand && has_operator
 }
 
+/// Explains why a math-like query has no result, e.g. `incomplete: missing )`.
+fn math_hint(query: &str) -> Option<String> {
+    if !looks_like_math(query) {
+        return None;
+    }
+
+    let err = match query.parse::<meval::Expr>() {
+        Ok(expr) => expr.eval().err()?,
+        Err(err) => err,
+    };
+
+    let hint = match err {
+        meval::Error::ParseError(ParseError::MissingRParen(n)) => {
+            format!("incomplete: missing {}", ")".repeat(n.max(1) as usize))
+        }
+        meval::Error::ParseError(ParseError::MissingArgument)
+        | meval::Error::RPNError(RPNError::NotEnoughOperands(_)) => "incomplete: missing operand".to_string(),
+        meval::Error::ParseError(ParseError::UnexpectedToken(pos)) => {
+            match query.get(pos..).and_then(|rest| rest.chars().next()) {
+                Some(c) => format!("unexpected '{}'", c),
+                None => "incomplete expression".to_string(),
+            }
+        }
+        meval::Error::RPNError(RPNError::MismatchedLParen(_)) => "incomplete: missing )".to_string(),
+        meval::Error::RPNError(RPNError::MismatchedRParen(_)) => "unbalanced: unexpected )".to_string(),
+        meval::Error::RPNError(RPNError::UnexpectedComma(_)) => "unexpected ,".to_string(),
+        meval::Error::RPNError(RPNError::TooManyOperands) => "missing operator".to_string(),
+        meval::Error::UnknownVariable(name) => format!("unknown variable {}", name),
+        meval::Error::Function(name, FuncEvalError::UnknownFunction) => format!("unknown function {}", name),
+        meval::Error::Function(name, FuncEvalError::NumberArgs(n)) => {
+            format!("{} expects {} argument{}", name, n, if n == 1 { "" } else { "s" })
+        }
+        meval::Error::Function(name, FuncEvalError::TooFewArguments) => format!("too few arguments for {}", name),
+        meval::Error::Function(name, FuncEvalError::TooManyArguments) => format!("too many arguments for {}", name),
+    };
+
+    Some(hint)
+}
 // Unit tests
 #[cfg(test)]
 mod tests {
-    use crate::entry::EntryKind;
     use super::*;
 
     #[test]
@@ -74,9 +261,9 @@ mod tests {
     fn empty_query_returns_first_n_entries() {
         let cfg = Rc::new(Config { max_results: 2, ..Default::default() });
         let entries = Rc::new(vec![
-            Entry { title: "App1".into(), kind: EntryKind::Result(String::new()) },
-            Entry { title: "App2".into(), kind: EntryKind::Result(String::new()) },
-            Entry { title: "App3".into(), kind: EntryKind::Result(String::new()) },
+            Entry { title: "App1".into(), subtitle: None, kind: EntryKind::Result(String::new()) },
+            Entry { title: "App2".into(), subtitle: None, kind: EntryKind::Result(String::new()) },
+            Entry { title: "App3".into(), subtitle: None, kind: EntryKind::Result(String::new()) },
         ]);
         let results = top_matches(&entries, "", cfg);
         assert_eq!(results.len(), 2);
@@ -91,4 +278,103 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].title, "4");
     }
+
+    #[test]
+    // Test that a trailing operator still previews the completed expression
+    fn incomplete_expression_previews_result() {
+        let cfg = Rc::new(Config::default());
+        let entries = Rc::new(vec![]);
+        let results = top_matches(&entries, "2*(3+4", cfg);
+        assert_eq!(results[0].title, "14");
+        assert_eq!(results[1].title, "incomplete: missing )");
+        assert!(matches!(results[1].kind, EntryKind::Hint));
+    }
+
+    #[test]
+    // Test that unknown functions produce a hint instead of nothing
+    fn unknown_function_hint() {
+        let cfg = Rc::new(Config::default());
+        let entries = Rc::new(vec![]);
+        let results = top_matches(&entries, "foo(2)", cfg);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "unknown function foo");
+    }
+
+    #[test]
+    // Test that hints never push app matches out of the result list
+    fn hint_does_not_outrank_matches() {
+        let cfg = Rc::new(Config { max_results: 1, ..Default::default() });
+        let entries = Rc::new(vec![
+            Entry { title: "foo(2)".into(), subtitle: None, kind: EntryKind::Result(String::new()) },
+        ]);
+        let results = top_matches(&entries, "foo(2)", cfg);
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0].kind, EntryKind::Result(_)));
+    }
+
+    #[test]
+    // Test that rescoring can reorder fuzzy matches
+    fn rescore_reorders_matches() {
+        let cfg = Rc::new(Config::default());
+        let entries = Rc::new(vec![
+            Entry { title: "Files".into(), subtitle: None, kind: EntryKind::Result(String::new()) },
+            Entry { title: "Firefox".into(), subtitle: None, kind: EntryKind::Result(String::new()) },
+        ]);
+        let results = top_matches(&entries, "fi", cfg.clone());
+        assert_eq!(results[0].title, "Files");
+
+        let results = top_matches_rescored(&entries, "fi", cfg, |entry, score| {
+            if entry.title == "Firefox" { score + 100 } else { score }
+        });
+        assert_eq!(results[0].title, "Firefox");
+    }
+
+    fn executable(name: &str) -> Entry {
+        Entry { title: name.into(), subtitle: None, kind: EntryKind::Executable(format!("/usr/bin/{name}").into()) }
+    }
+
+    #[test]
+    // Test that an exact alias comes first, ahead of better fuzzy matches
+    fn alias_outranks_fuzzy_matches() {
+        let mut cfg = Config::default();
+        cfg.aliases.insert("ht".into(), "htop".into());
+        let cfg = Rc::new(cfg);
+        let entries = Rc::new(vec![executable("ht"), executable("htop"), executable("bash")]);
+
+        let results = top_matches(&entries, "ht", cfg.clone());
+        let titles: Vec<&str> = results.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["htop", "ht"]);
+        // Only an exact alias counts
+        assert_eq!(top_matches(&entries, "h", cfg)[0].title, "ht");
+    }
+
+    #[test]
+    // Test that pinned entries lead the empty-query view in config order
+    fn pinned_entries_lead_empty_query() {
+        let cfg = Rc::new(Config { pinned: vec!["vim".into(), "bash".into(), "missing".into()], ..Default::default() });
+        let entries = Rc::new(vec![executable("bash"), executable("htop"), executable("vim"), executable("zsh")]);
+        let results = top_matches(&entries, "", cfg);
+        let titles: Vec<&str> = results.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["vim", "bash", "htop", "zsh"]);
+    }
+
+    #[test]
+    // Test that only path and URL arguments split a query into app and files
+    fn launch_args_need_paths_or_urls() {
+        let (name, args) = split_launch_args("gimp ~/Pictures/foo.png").unwrap();
+        assert_eq!((name, args), ("gimp", vec!["~/Pictures/foo.png".to_string()]));
+        assert_eq!(split_launch_args("code .").unwrap().1, ["."]);
+        assert!(split_launch_args("firefox https://example.org ./page.html").is_some());
+        assert!(split_launch_args("visual studio").is_none());
+        assert!(split_launch_args("code").is_none());
+        assert_eq!(complete_query("gimp visual"), None);
+    }
+
+    #[test]
+    // Test that plain app names never produce math hints
+    fn plain_query_has_no_hint() {
+        let cfg = Rc::new(Config::default());
+        let entries = Rc::new(vec![]);
+        assert!(top_matches(&entries, "firefox", cfg).is_empty());
+    }
 }
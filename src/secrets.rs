@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use gio::{BusType, Cancellable, DBusCallFlags, DBusConnection, DBusError, IOErrorEnum};
+use glib::{ToVariant, Variant, VariantTy};
+
+/// The bus name of the freedesktop Secret Service (GNOME Keyring, KWallet, KeePassXC).
+pub const SERVICE: &str = "org.freedesktop.secrets";
+const PATH: &str = "/org/freedesktop/secrets";
+const SERVICE_INTERFACE: &str = "org.freedesktop.Secret.Service";
+const COLLECTION_INTERFACE: &str = "org.freedesktop.Secret.Collection";
+/// Where new secrets go: the user's default collection, usually the login keyring.
+const DEFAULT_COLLECTION: &str = "/org/freedesktop/secrets/aliases/default";
+/// The path the service returns when a request needs no prompt.
+const NO_PROMPT: &str = "/";
+
+const TIMEOUT_MS: i32 = 2000;
+
+/// A client for the user's Secret Service. It opens a `plain` session: secrets
+/// cross the session bus unencrypted, where only the user's processes see them.
+pub struct SecretService {
+    conn: DBusConnection,
+    session: Variant,
+}
+
+impl SecretService {
+    /// Opens a session with the service on the session bus, or `None` when
+    /// there is no session bus or nothing on it provides the service.
+    pub fn user() -> Result<Option<Self>, glib::Error> {
+        let Ok(conn) = gio::bus_get_sync(BusType::Session, None::<&Cancellable>) else { return Ok(None) };
+        match Self::on(conn) {
+            Ok(secrets) => Ok(Some(secrets)),
+            Err(e) if e.matches(DBusError::ServiceUnknown) || e.matches(DBusError::NameHasNoOwner) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Talks to whatever owns [`SERVICE`] on `conn`; tests pass a private bus.
+    pub fn on(conn: DBusConnection) -> Result<Self, glib::Error> {
+        let args = ("plain", "".to_variant()).to_variant();
+        let reply = call(&conn, PATH, SERVICE_INTERFACE, "OpenSession", Some(&args), "(vo)")?;
+        Ok(Self { conn, session: reply.child_value(1) })
+    }
+
+    /// The secret of an item filed under `attributes`. Items in a locked
+    /// collection are an error rather than missing, so callers don't replace them.
+    pub fn lookup(&self, attributes: &HashMap<&str, &str>) -> Result<Option<Vec<u8>>, glib::Error> {
+        let args = (attributes.clone(),).to_variant();
+        let reply = call(&self.conn, PATH, SERVICE_INTERFACE, "SearchItems", Some(&args), "(aoao)")?;
+        let (unlocked, locked) = (reply.child_value(0), reply.child_value(1));
+        if unlocked.n_children() == 0 {
+            return if locked.n_children() == 0 { Ok(None) } else { Err(locked_error()) };
+        }
+
+        let items = Variant::array_from_iter_with_type(VariantTy::OBJECT_PATH, [unlocked.child_value(0)]);
+        let args = Variant::tuple_from_iter([items, self.session.clone()]);
+        let reply = call(&self.conn, PATH, SERVICE_INTERFACE, "GetSecrets", Some(&args), "(a{o(oayays)})")?;
+        // Each entry maps the item to `(session, parameters, value, content type)`.
+        Ok(reply.child_value(0).try_child_value(0).and_then(|entry| entry.child_value(1).child_value(2).get()))
+    }
+
+    /// Files `secret` under `attributes` in the default collection, replacing
+    /// an item with the same attributes.
+    pub fn store(&self, label: &str, attributes: &HashMap<&str, &str>, secret: &[u8]) -> Result<(), glib::Error> {
+        let properties: HashMap<&str, Variant> = HashMap::from([
+            ("org.freedesktop.Secret.Item.Label", label.to_variant()),
+            ("org.freedesktop.Secret.Item.Attributes", attributes.clone().to_variant()),
+        ]);
+        let secret = Variant::tuple_from_iter([
+            self.session.clone(),
+            Vec::<u8>::new().to_variant(),
+            secret.to_vec().to_variant(),
+            "application/octet-stream".to_variant(),
+        ]);
+        let args = Variant::tuple_from_iter([properties.to_variant(), secret, true.to_variant()]);
+        let reply = call(&self.conn, DEFAULT_COLLECTION, COLLECTION_INTERFACE, "CreateItem", Some(&args), "(oo)")?;
+        // Unlocking would need the service's own prompt; leave that to the user's session.
+        if reply.child_value(1).str() != Some(NO_PROMPT) {
+            return Err(locked_error());
+        }
+        Ok(())
+    }
+}
+
+fn locked_error() -> glib::Error {
+    glib::Error::new(IOErrorEnum::PermissionDenied, "the keyring is locked")
+}
+
+fn call(
+    conn: &DBusConnection,
+    path: &str,
+    interface: &str,
+    method: &str,
+    args: Option<&Variant>,
+    reply_type: &str,
+) -> Result<Variant, glib::Error> {
+    conn.call_sync(
+        Some(SERVICE),
+        path,
+        interface,
+        method,
+        args,
+        VariantTy::new(reply_type).ok(),
+        DBusCallFlags::NONE,
+        TIMEOUT_MS,
+        None::<&Cancellable>,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use crate::test_bus::PrivateBus;
+
+    const STUB_INTERFACES: &str = "<node>
+        <interface name='org.freedesktop.Secret.Service'>
+            <method name='OpenSession'><arg type='s'/><arg type='v'/><arg type='v' direction='out'/><arg type='o' direction='out'/></method>
+            <method name='SearchItems'><arg type='a{ss}'/><arg type='ao' direction='out'/><arg type='ao' direction='out'/></method>
+            <method name='GetSecrets'><arg type='ao'/><arg type='o'/><arg type='a{o(oayays)}' direction='out'/></method>
+        </interface>
+        <interface name='org.freedesktop.Secret.Collection'>
+            <method name='CreateItem'><arg type='a{sv}'/><arg type='(oayays)'/><arg type='b'/><arg type='o' direction='out'/><arg type='o' direction='out'/></method>
+        </interface>
+    </node>";
+
+    type Items = Arc<Mutex<Vec<(HashMap<String, String>, Vec<u8>)>>>;
+
+    fn item_path(index: usize) -> String {
+        format!("/org/freedesktop/secrets/collection/login/{index}")
+    }
+
+    fn reply(invocation: gio::DBusMethodInvocation, ty: &str, text: &str) {
+        invocation.return_value(Some(&Variant::parse(VariantTy::new(ty).ok(), text).unwrap()));
+    }
+
+    /// A stand-in for the Secret Service keeping items in memory, in one
+    /// always-unlocked collection.
+    fn serve_stub_service(bus: &PrivateBus) {
+        let items: Items = Arc::default();
+        bus.serve(SERVICE, move |conn| {
+            let node = gio::DBusNodeInfo::for_xml(STUB_INTERFACES).unwrap();
+            let service_items = items.clone();
+            conn.register_object(
+                PATH,
+                &node.lookup_interface(SERVICE_INTERFACE).unwrap(),
+                move |_, _, _, _, method, args, invocation| {
+                    let items = service_items.lock().unwrap();
+                    match method {
+                        "OpenSession" => reply(invocation, "(vo)", "(<''>, '/org/freedesktop/secrets/session/1')"),
+                        "SearchItems" => {
+                            let wanted: HashMap<String, String> = args.child_value(0).get().unwrap();
+                            let found: Vec<String> = (0..items.len())
+                                .filter(|&i| wanted.iter().all(|(k, v)| items[i].0.get(k) == Some(v)))
+                                .map(|i| format!("'{}'", item_path(i)))
+                                .collect();
+                            reply(invocation, "(aoao)", &format!("([{}], [])", found.join(", ")));
+                        }
+                        _ => {
+                            let path = args.child_value(0).child_value(0).str().unwrap().to_string();
+                            let index: usize = path.rsplit('/').next().unwrap().parse().unwrap();
+                            let bytes: Vec<String> = items[index].1.iter().map(u8::to_string).collect();
+                            let text = format!(
+                                "({{'{path}': ('/org/freedesktop/secrets/session/1', [], [{}], 'application/octet-stream')}},)",
+                                bytes.join(", ")
+                            );
+                            reply(invocation, "(a{o(oayays)})", &text);
+                        }
+                    }
+                },
+                |_, _, _, _, _| unreachable!(),
+                |_, _, _, _, _, _| false,
+            )
+            .unwrap();
+            conn.register_object(
+                DEFAULT_COLLECTION,
+                &node.lookup_interface(COLLECTION_INTERFACE).unwrap(),
+                move |_, _, _, _, _, args, invocation| {
+                    let attributes = args
+                        .child_value(0)
+                        .lookup_value("org.freedesktop.Secret.Item.Attributes", None)
+                        .and_then(|value| value.get())
+                        .unwrap();
+                    let secret: Vec<u8> = args.child_value(1).child_value(2).get().unwrap();
+                    let mut items = items.lock().unwrap();
+                    items.push((attributes, secret));
+                    let text = format!("('{}', '/')", item_path(items.len() - 1));
+                    reply(invocation, "(oo)", &text);
+                },
+                |_, _, _, _, _| unreachable!(),
+                |_, _, _, _, _, _| false,
+            )
+            .unwrap();
+        });
+    }
+
+    /// Runs against a private bus with a stub service, so no keyring is
+    /// touched. Skipped when `dbus-daemon` isn't installed.
+    #[test]
+    fn stores_and_looks_up_secrets_on_a_stub_service() {
+        let Some(bus) = PrivateBus::start() else { return };
+        serve_stub_service(&bus);
+
+        let secrets = SecretService::on(bus.connect()).unwrap();
+        let attributes = HashMap::from([("application", "scout"), ("purpose", "test")]);
+        assert_eq!(secrets.lookup(&attributes).unwrap(), None);
+
+        secrets.store("Scout test key", &attributes, &[7; 32]).unwrap();
+        assert_eq!(secrets.lookup(&attributes).unwrap(), Some(vec![7; 32]));
+        assert_eq!(secrets.lookup(&HashMap::from([("application", "other")])).unwrap(), None);
+    }
+}
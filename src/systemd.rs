@@ -0,0 +1,266 @@
+use std::collections::HashSet;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::path::Path;
+use std::pin::Pin;
+use gio::{BusType, Cancellable, DBusCallFlags, DBusConnection, IOErrorEnum};
+use glib::{ToVariant, Variant, VariantTy};
+use crate::entry::{Unit, UnitAction};
+
+/// The bus name systemd's manager owns, on the system bus and on each user's session bus.
+pub const SERVICE: &str = "org.freedesktop.systemd1";
+const PATH: &str = "/org/freedesktop/systemd1";
+const MANAGER: &str = "org.freedesktop.systemd1.Manager";
+
+/// Replies from the manager are quick; a stuck one shouldn't freeze the window for long.
+const TIMEOUT_MS: i32 = 2000;
+
+/// A client for the user's service manager (`systemctl --user`) over D-Bus.
+pub struct Systemd {
+    conn: DBusConnection,
+}
+
+impl Systemd {
+    /// Connects to the session bus, where the user manager listens.
+    pub fn user() -> Result<Self, glib::Error> {
+        Ok(Self::on(gio::bus_get_sync(BusType::Session, None::<&Cancellable>)?))
+    }
+
+    /// Talks to whatever owns [`SERVICE`] on `conn`; tests pass a private bus.
+    pub fn on(conn: DBusConnection) -> Self {
+        Self { conn }
+    }
+
+    /// Units the manager has loaded, plus installed units it hasn't, such as
+    /// a stopped service nothing depends on; those are listed as inactive.
+    pub async fn units(&self) -> Result<Vec<Unit>, glib::Error> {
+        let loaded = self.call_future("ListUnits", "(a(ssssssouso))").await?;
+        let files = self.call_future("ListUnitFiles", "(a(ss))").await?;
+        let mut units = parse_units(&loaded.child_value(0));
+        add_unit_files(&mut units, &files.child_value(0));
+        Ok(units)
+    }
+
+    /// Queues a start, stop or restart job for `unit`, replacing any pending job.
+    /// Logs come from the journal, not the manager, so asking for them here is an error.
+    pub fn run(&self, action: UnitAction, unit: &str) -> Result<(), glib::Error> {
+        let method = match action {
+            UnitAction::Start => "StartUnit",
+            UnitAction::Stop => "StopUnit",
+            UnitAction::Restart => "RestartUnit",
+            UnitAction::Logs => {
+                return Err(glib::Error::new(IOErrorEnum::NotSupported, "logs are read from the journal"));
+            }
+        };
+        self.call(method, Some(&(unit, "replace").to_variant()), "(o)")?;
+        Ok(())
+    }
+
+    /// Moves `pids` into a new transient scope unit `name`, so their resources
+    /// are accounted and OOM-handled apart from scout's.
+    pub fn start_scope(&self, name: &str, pids: &[u32]) -> Result<(), glib::Error> {
+        let properties: Vec<(&str, Variant)> = vec![
+            ("PIDs", pids.to_vec().to_variant()),
+            // Unload the unit once its processes are gone, even if they failed.
+            ("CollectMode", "inactive-or-failed".to_variant()),
+        ];
+        let aux: Vec<(&str, Vec<(&str, Variant)>)> = Vec::new();
+        self.call("StartTransientUnit", Some(&(name, "fail", properties, aux).to_variant()), "(o)")?;
+        Ok(())
+    }
+
+    fn call(&self, method: &str, args: Option<&Variant>, reply_type: &str) -> Result<Variant, glib::Error> {
+        self.conn.call_sync(
+            Some(SERVICE),
+            PATH,
+            MANAGER,
+            method,
+            args,
+            VariantTy::new(reply_type).ok(),
+            DBusCallFlags::NONE,
+            TIMEOUT_MS,
+            None::<&Cancellable>,
+        )
+    }
+
+    /// Calls `method` without arguments, answering on the thread's main context.
+    fn call_future(
+        &self,
+        method: &str,
+        reply_type: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Variant, glib::Error>> + 'static>> {
+        self.conn.call_future(
+            Some(SERVICE),
+            PATH,
+            MANAGER,
+            method,
+            None,
+            VariantTy::new(reply_type).ok(),
+            DBusCallFlags::NONE,
+            TIMEOUT_MS,
+        )
+    }
+}
+
+/// A scope name for an app scout launches, following the XDG desktop cgroup
+/// convention `app-<launcher>-<app id>-<random>.scope`.
+pub fn app_scope_name(app_id: &str) -> String {
+    let random = RandomState::new().build_hasher().finish();
+    format!("app-scout-{}-{:016x}.scope", escape(app_id), random)
+}
+
+/// Escapes `name` as `systemd-escape` does, so a `-` inside an app id can't be
+/// mistaken for the separators around it.
+fn escape(name: &str) -> String {
+    let mut escaped = String::new();
+    for (i, byte) in name.bytes().enumerate() {
+        match byte {
+            b'/' => escaped.push('-'),
+            b'.' if i > 0 => escaped.push('.'),
+            b if b.is_ascii_alphanumeric() || b == b':' || b == b'_' => escaped.push(b as char),
+            b => escaped.push_str(&format!("\\x{:02x}", b)),
+        }
+    }
+    escaped
+}
+
+/// `ListUnits` returns `(name, description, load state, active state, sub
+/// state, followed, object path, job id, job type, job path)` per unit.
+/// Units systemd only knows by reference (`not-found`) are left out.
+fn parse_units(units: &Variant) -> Vec<Unit> {
+    units
+        .iter()
+        .filter_map(|unit| {
+            let field = |n: usize| unit.try_child_value(n).and_then(|v| v.str().map(str::to_string));
+            if field(2)? == "not-found" {
+                return None;
+            }
+            Some(Unit {
+                name: field(0)?,
+                description: field(1)?,
+                active_state: field(3)?,
+                sub_state: field(4)?,
+            })
+        })
+        .collect()
+}
+
+/// `ListUnitFiles` returns `(path, enablement state)` per installed unit file.
+/// Units not in `units` yet are added as inactive, leaving out templates and
+/// masked or aliased units, which can't be started by that name.
+fn add_unit_files(units: &mut Vec<Unit>, files: &Variant) {
+    let mut known: HashSet<String> = units.iter().map(|unit| unit.name.clone()).collect();
+    for file in files.iter() {
+        let (path, state) = (file.child_value(0), file.child_value(1));
+        let (Some(path), Some(state)) = (path.str(), state.str()) else { continue };
+        let Some(name) = Path::new(path).file_name().and_then(|name| name.to_str()) else { continue };
+        if matches!(state, "masked" | "alias") || name.contains("@.") || !known.insert(name.to_string()) {
+            continue;
+        }
+        units.push(Unit {
+            name: name.to_string(),
+            description: name.to_string(),
+            active_state: "inactive".to_string(),
+            sub_state: "dead".to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+    use crate::test_bus::PrivateBus;
+
+    const STUB_INTERFACE: &str = "<node><interface name='org.freedesktop.systemd1.Manager'>
+        <method name='ListUnits'><arg type='a(ssssssouso)' direction='out'/></method>
+        <method name='ListUnitFiles'><arg type='a(ss)' direction='out'/></method>
+        <method name='StartUnit'><arg type='s'/><arg type='s'/><arg type='o' direction='out'/></method>
+        <method name='StopUnit'><arg type='s'/><arg type='s'/><arg type='o' direction='out'/></method>
+        <method name='RestartUnit'><arg type='s'/><arg type='s'/><arg type='o' direction='out'/></method>
+        <method name='StartTransientUnit'><arg type='s'/><arg type='s'/><arg type='a(sv)'/><arg type='a(sa(sv))'/><arg type='o' direction='out'/></method>
+    </interface></node>";
+
+    const STUB_UNITS: &str = "([
+        ('syncthing.service', 'Syncthing', 'loaded', 'active', 'running', '', '/org/freedesktop/systemd1/unit/syncthing_2eservice', 0, '', '/'),
+        ('backup.timer', 'Nightly backup', 'loaded', 'inactive', 'dead', '', '/org/freedesktop/systemd1/unit/backup_2etimer', 0, '', '/'),
+        ('gone.service', 'gone.service', 'not-found', 'inactive', 'dead', '', '/org/freedesktop/systemd1/unit/gone_2eservice', 0, '', '/')
+    ],)";
+
+    /// Installed unit files; `devserver.service` isn't loaded.
+    const STUB_UNIT_FILES: &str = "([
+        ('/home/me/.config/systemd/user/syncthing.service', 'enabled'),
+        ('/home/me/.config/systemd/user/devserver.service', 'disabled'),
+        ('/usr/lib/systemd/user/backup.timer', 'static'),
+        ('/usr/lib/systemd/user/worker@.service', 'static'),
+        ('/home/me/.config/systemd/user/tracker.service', 'masked')
+    ],)";
+
+    /// Serves a stand-in for systemd's manager on `bus`, reporting each job
+    /// it is asked to queue.
+    fn serve_stub_manager(bus: &PrivateBus) -> mpsc::Receiver<String> {
+        let (jobs_tx, jobs_rx) = mpsc::channel();
+        bus.serve(SERVICE, move |conn| {
+            let node = gio::DBusNodeInfo::for_xml(STUB_INTERFACE).unwrap();
+            let interface = node.lookup_interface(MANAGER).unwrap();
+            conn.register_object(
+                PATH,
+                &interface,
+                move |_, _, _, _, method, args, invocation| {
+                    let listing = match method {
+                        "ListUnits" => Some(("(a(ssssssouso))", STUB_UNITS)),
+                        "ListUnitFiles" => Some(("(a(ss))", STUB_UNIT_FILES)),
+                        _ => None,
+                    };
+                    if let Some((ty, text)) = listing {
+                        invocation.return_value(Some(&Variant::parse(VariantTy::new(ty).ok(), text).unwrap()));
+                        return;
+                    }
+                    let unit = args.child_value(0).str().unwrap_or_default().to_string();
+                    jobs_tx.send(format!("{method} {unit}")).unwrap();
+                    let job = Variant::parse(VariantTy::new("(o)").ok(), "('/org/freedesktop/systemd1/job/1',)");
+                    invocation.return_value(Some(&job.unwrap()));
+                },
+                |_, _, _, _, _| unreachable!(),
+                |_, _, _, _, _, _| false,
+            )
+            .unwrap();
+        });
+        jobs_rx
+    }
+
+    #[test]
+    fn scope_names_escape_the_app_id() {
+        let name = app_scope_name("code-oss");
+        assert!(name.starts_with("app-scout-code\\x2doss-"), "{name}");
+        assert!(name.ends_with(".scope"));
+        assert_ne!(name, app_scope_name("code-oss"));
+        assert_eq!(escape("org.gnome.Nautilus"), "org.gnome.Nautilus");
+        assert_eq!(escape(".hidden app"), "\\x2ehidden\\x20app");
+    }
+
+    /// Runs against a private bus with a stub manager, so neither a session
+    /// bus nor systemd is needed. Skipped when `dbus-daemon` isn't installed.
+    #[test]
+    fn lists_units_and_queues_jobs_on_a_stub_manager() {
+        let Some(bus) = PrivateBus::start() else { return };
+        let jobs = serve_stub_manager(&bus);
+
+        let systemd = Systemd::on(bus.connect());
+        let units = glib::MainContext::new().block_on(systemd.units()).unwrap();
+        let names: Vec<&str> = units.iter().map(|unit| unit.name.as_str()).collect();
+        assert_eq!(names, ["syncthing.service", "backup.timer", "devserver.service"]);
+        assert_eq!((units[0].active_state.as_str(), units[0].sub_state.as_str()), ("active", "running"));
+        assert_eq!(units[1].description, "Nightly backup");
+        // Unloaded units can still be started.
+        assert_eq!(units[2].active_state, "inactive");
+
+        systemd.run(UnitAction::Restart, "syncthing.service").unwrap();
+        assert_eq!(jobs.recv_timeout(Duration::from_secs(5)).unwrap(), "RestartUnit syncthing.service");
+        assert!(systemd.run(UnitAction::Logs, "syncthing.service").is_err());
+        systemd.start_scope("app-scout-firefox-1.scope", &[4242]).unwrap();
+        assert_eq!(jobs.recv_timeout(Duration::from_secs(5)).unwrap(), "StartTransientUnit app-scout-firefox-1.scope");
+    }
+}
@@ -0,0 +1,82 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use gio::{Cancellable, DBusCallFlags, DBusConnection, DBusConnectionFlags};
+use glib::ToVariant;
+
+/// A private `dbus-daemon` for tests against stub services. It is killed and
+/// reaped when dropped, so a failing assertion doesn't leave it running.
+pub struct PrivateBus {
+    daemon: Child,
+    pub address: String,
+}
+
+impl PrivateBus {
+    /// `None` when `dbus-daemon` isn't installed, so the caller can skip.
+    pub fn start() -> Option<Self> {
+        let daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .ok()?;
+        let mut bus = Self { daemon, address: String::new() };
+        let stdout = bus.daemon.stdout.take()?;
+        BufReader::new(stdout).read_line(&mut bus.address).ok()?;
+        bus.address = bus.address.trim().to_string();
+        Some(bus)
+    }
+
+    pub fn connect(&self) -> DBusConnection {
+        connect(&self.address)
+    }
+
+    /// Owns `name` on the bus from a thread with its own main loop, after
+    /// `register` exported the stub's objects on that thread's connection.
+    pub fn serve(&self, name: &'static str, register: impl FnOnce(&DBusConnection) + Send + 'static) {
+        let address = self.address.clone();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        thread::spawn(move || {
+            let context = glib::MainContext::new();
+            context
+                .with_thread_default(|| {
+                    let conn = connect(&address);
+                    register(&conn);
+                    conn.call_sync(
+                        Some("org.freedesktop.DBus"),
+                        "/org/freedesktop/DBus",
+                        "org.freedesktop.DBus",
+                        "RequestName",
+                        Some(&(name, 0u32).to_variant()),
+                        None,
+                        DBusCallFlags::NONE,
+                        -1,
+                        None::<&Cancellable>,
+                    )
+                    .unwrap();
+                    ready_tx.send(()).unwrap();
+                    glib::MainLoop::new(Some(&context), false).run();
+                })
+                .unwrap();
+        });
+        ready_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
+
+fn connect(address: &str) -> DBusConnection {
+    DBusConnection::for_address_sync(
+        address,
+        DBusConnectionFlags::AUTHENTICATION_CLIENT | DBusConnectionFlags::MESSAGE_BUS_CONNECTION,
+        None,
+        None::<&Cancellable>,
+    )
+    .unwrap()
+}
@@ -7,15 +7,30 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use gdk::glib::Propagation;
 use gdk::keys::constants as key;
-use crate::config::{Config, Theme};
-use crate::entry::{Entry, EntryKind};
-use crate::icon::{create_app_icon_widget, create_generic_icon_widget};
-use crate::launcher::{launch_gui_app, launch_terminal_application, needs_terminal};
-use crate::search::{get_entries, top_matches};
-
-pub fn build_ui(app: &Application, cfg: Rc<Config>) -> Result<(), String> {
+use crate::config::{self, Config, Theme};
+use crate::entry::{Entry, EntryKind, PanelAction, UnitAction};
+use crate::icon::{
+    create_app_icon_widget, create_content_type_icon_widget, create_file_icon_widget, create_generic_icon_widget,
+    create_named_icon_widget, create_window_icon_widget,
+};
+use crate::actions;
+use crate::launcher::{activate, activate_alternate};
+use crate::lua::LuaHooks;
+use crate::plugins::Plugins;
+use crate::providers::clipboard::ClipboardStore;
+use crate::providers::{follow_up, web_search, Providers};
+use crate::search::{complete_query, get_entries, top_matches_rescored};
+
+pub fn build_ui(
+    app: &Application,
+    cfg: Rc<Config>,
+    clipboard: Rc<ClipboardStore>,
+    plugins: Rc<Plugins>,
+    lua: Rc<LuaHooks>,
+) -> Result<(), String> {
     // Data
-    let all_apps = Rc::new(get_entries());
+    let all_apps = Rc::new(RefCell::new(Rc::new(get_entries(&cfg))));
+    let providers = Rc::new(Providers::new(clipboard, plugins.clone()));
     let current_results: Rc<RefCell<Vec<Entry>>> = Rc::new(RefCell::new(Vec::new()));
 
     // Window
@@ -56,29 +71,23 @@ pub fn build_ui(app: &Application, cfg: Rc<Config>) -> Result<(), String> {
     // Track if this is the first change (to clear hint text)
     let hint_cleared: Rc<RefCell<bool>> = Rc::new(RefCell::new(false));
 
-    // Render helper: compute top matches, rebuild rows, select first.
-    let render_results = {
+    // Show helper: replace the rows with `entries` and select the first.
+    let show_entries = {
         let list = list.clone();
-        let all_apps = all_apps.clone();
         let cfg = cfg.clone();
         let current_results = current_results.clone();
-        move |query: &str| {
+        let plugins = plugins.clone();
+        move |entries: Vec<Entry>| {
             // Clear rows
             list.foreach(|child| list.remove(child));
 
-            // Compute matches
-            let match_cfg = cfg.clone();
-            let matches = top_matches(&all_apps, query, match_cfg);
-
             // Update "model" backing the list
-            *current_results.borrow_mut() = matches.clone();
-
-            //Create new config
+            *current_results.borrow_mut() = entries.clone();
 
             // Add rows
-            for entry in matches {
+            for entry in entries {
                 let inner_cfg = cfg.clone();
-                list.add(&render_row(&entry, inner_cfg));
+                list.add(&render_row(&entry, inner_cfg, &plugins));
             }
 
             list.show_all();
@@ -88,9 +97,54 @@ pub fn build_ui(app: &Application, cfg: Rc<Config>) -> Result<(), String> {
         }
     };
 
+    // Render helper: compute top matches and show them.
+    let render_results = Rc::new({
+        let all_apps = all_apps.clone();
+        let providers = providers.clone();
+        let cfg = cfg.clone();
+        let show_entries = show_entries.clone();
+        let lua = lua.clone();
+        move |query: &str| {
+            // Compute matches
+            let match_cfg = cfg.clone();
+            let matches = providers.search(query, &cfg).unwrap_or_else(|| {
+                let all_apps = all_apps.borrow().clone();
+                let hooks = lua.for_query(query);
+                // Script rows come first, so they get at most half the list.
+                let mut matches = hooks.on_query();
+                matches.truncate(cfg.max_results.div_ceil(2));
+                matches.extend(top_matches_rescored(&all_apps, query, match_cfg, |entry, score| {
+                    hooks.score(entry, score)
+                }));
+                matches.truncate(cfg.max_results);
+                providers.annotate(&mut matches, &cfg);
+                // Offer a web search when nothing local matched (hints don't count).
+                if matches.iter().all(|e| matches!(e.kind, EntryKind::Hint))
+                    && let Some(fallback) = web_search::fallback(query, &cfg)
+                {
+                    matches.push(fallback);
+                }
+                matches
+            });
+
+            show_entries(matches);
+        }
+    });
+
     // Initial fill (top k apps)
     render_results("");
 
+    // Script providers and the unit list answer later; show their rows once they arrive.
+    providers.on_update({
+        let render_results = Rc::downgrade(&render_results);
+        let search = entry.clone();
+        move || {
+            if let Some(render_results) = render_results.upgrade() {
+                render_results(&search.text());
+            }
+        }
+    });
+
     // Update list on typing
     entry.connect_changed({
         let render_results = render_results.clone();
@@ -126,64 +180,109 @@ pub fn build_ui(app: &Application, cfg: Rc<Config>) -> Result<(), String> {
         }
     });
 
-    // Launch on row activation (double click or Enter activation)
-    list.connect_row_activated({
+    // Run the entry at a row index; `alternate` selects the Shift+Enter action.
+    let activate_row = {
         let current_results = current_results.clone();
         let window_clone = window.clone();
-        let app_clone = app.clone();
         let cfg = cfg.clone();
-        move |_, row| {
-            let idx = row.index() as usize;
+        let show_entries = show_entries.clone();
+        let render_results = render_results.clone();
+        let search = entry.clone();
+        let lua = lua.clone();
+        move |idx: usize, alternate: bool| {
             let maybe_entry = current_results.borrow().get(idx).cloned();
             if let Some(entry) = maybe_entry {
+                // Hints are informational only; keep the window open for editing.
+                if matches!(entry.kind, EntryKind::Hint) {
+                    return;
+                }
+
+                // Some entries lead to more rows (skin tones, confirmations) rather than running.
+                if let Some(rows) = follow_up(&entry, alternate) {
+                    show_entries(rows);
+                    return;
+                }
+                if matches!(entry.kind, EntryKind::Cancel) {
+                    render_results(&search.text());
+                    return;
+                }
+                if !alternate && lua.on_activate(&entry) {
+                    window_clone.close();
+                    return;
+                }
+
                 // Hide window immediately for better UX
                 window_clone.hide();
 
-                match &entry.kind {
-                    EntryKind::App(appinfo) => {
-                        if needs_terminal(appinfo) {
-                            let exec_path = appinfo.executable();
-                            let exec = exec_path.to_string_lossy().into_owned();
-                            let term = cfg.terminal_emulator.to_string();
-                            launch_terminal_application(&[exec], &[term])
-                                .map_err(|e| format!("Failed to launch terminal app: {}", e))
-                                .unwrap_or_else(|err| eprintln!("Launch failed: {err}"));
-                            app_clone.quit();
-                            return;
-                        }
-
-                        if let Err(err) = launch_gui_app(appinfo) {
-                            eprintln!("Launch failed: {err}");
-                        } else {
-                            app_clone.quit();
-                        }
-                    }
-                    EntryKind::Action(action) => {
-                        if let Err(err) = crate::launcher::launch_system_action(action) {
-                            eprintln!("Action failed: {err}");
-                        } else {
-                            app_clone.quit();
-                        }
-                    }
-
-                    EntryKind::Result(_math_entry) => {
-                        // Do nothing; we just show the result
-                        app_clone.quit();
-                    }
+                let result = if alternate {
+                    activate_alternate(&entry, &cfg)
+                } else {
+                    activate(&entry, &cfg)
+                };
+                if let Err(err) = result {
+                    // Bring the window back with the error rather than leaving a process without one.
+                    eprintln!("Launch failed: {err}");
+                    show_entries(vec![Entry::hint(format!("Launch failed: {err}"))]);
+                    window_clone.present();
+                } else {
+                    // Quits unless running as a daemon, which keeps the application held.
+                    window_clone.close();
                 }
             }
         }
+    };
+
+    // Hide an app row for good: add it to `hidden_apps` and drop it from this window.
+    let hide_row = {
+        let current_results = current_results.clone();
+        let all_apps = all_apps.clone();
+        let render_results = render_results.clone();
+        let search = entry.clone();
+        move |idx: usize| {
+            let Some(id) = current_results.borrow().get(idx).and_then(Entry::app_id) else { return };
+            if let Err(e) = config::add_to_list("hidden_apps", &id) {
+                eprintln!("Failed to hide {id}: {e}");
+                return;
+            }
+            let remaining: Vec<Entry> = all_apps
+                .borrow()
+                .iter()
+                .filter(|entry| entry.app_id().as_deref() != Some(id.as_str()))
+                .cloned()
+                .collect();
+            *all_apps.borrow_mut() = Rc::new(remaining);
+            render_results(&search.text());
+        }
+    };
+
+    // Replace the rows with the actions panel of the row at an index; false if it has none.
+    let open_panel = {
+        let current_results = current_results.clone();
+        let show_entries = show_entries.clone();
+        move |idx: usize| {
+            let panel = current_results.borrow().get(idx).and_then(actions::panel);
+            let Some(rows) = panel else { return false };
+            show_entries(rows);
+            true
+        }
+    };
+
+    // Launch on row activation (double click or Enter activation)
+    list.connect_row_activated({
+        let activate_row = activate_row.clone();
+        move |_, row| activate_row(row.index() as usize, false)
     });
 
-    // Key handling: Up/Down, Enter, Escape
+    // Key handling: Up/Down, Enter, Shift+Enter, Tab/Right, Ctrl+Delete, Escape
     window.connect_key_press_event({
         let list = list.clone();
-        let app_clone = app.clone();
-        move |_, ev| {
+        let activate_row = activate_row.clone();
+        let search = entry.clone();
+        move |window, ev| {
             let keyval = ev.keyval();
 
             if keyval == key::Escape {
-                app_clone.quit();
+                window.close();
                 return Propagation::Stop;
             }
 
@@ -211,7 +310,41 @@ pub fn build_ui(app: &Application, cfg: Rc<Config>) -> Result<(), String> {
                 return Propagation::Stop;
             }
 
-            if (keyval == key::Return || keyval == key::KP_Enter)
+            // Tab completes a path being typed, e.g. `gimp ~/Pic`, before it opens the panel.
+            if keyval == key::Tab
+                && let Some(completed) = complete_query(&search.text())
+            {
+                search.set_text(&completed);
+                search.set_position(-1);
+                return Propagation::Stop;
+            }
+
+            // Right only opens the panel from the end of the query, so the cursor can still move.
+            let at_end = search.position() >= i32::from(search.text_length());
+            if keyval == key::Tab || (keyval == key::Right && at_end) {
+                let opened = list.selected_row().is_some_and(|sel| open_panel(sel.index() as usize));
+                // Tab never moves focus out of the search field.
+                if opened || keyval == key::Tab {
+                    return Propagation::Stop;
+                }
+            }
+
+            if keyval == key::Delete && ev.state().contains(gdk::ModifierType::CONTROL_MASK) {
+                if let Some(sel) = list.selected_row() {
+                    hide_row(sel.index() as usize);
+                }
+                return Propagation::Stop;
+            }
+
+            let is_enter = keyval == key::Return || keyval == key::KP_Enter;
+            if is_enter && ev.state().contains(gdk::ModifierType::SHIFT_MASK) {
+                if let Some(sel) = list.selected_row() {
+                    activate_row(sel.index() as usize, true);
+                }
+                return Propagation::Stop;
+            }
+
+            if is_enter
                 && list
                 .selected_row()
                 .map(|sel| {
@@ -234,31 +367,111 @@ pub fn build_ui(app: &Application, cfg: Rc<Config>) -> Result<(), String> {
     Ok(())
 }
 
+fn panel_icon(action: &PanelAction) -> &'static str {
+    match action {
+        PanelAction::Launch | PanelAction::DesktopAction(_) => "system-run",
+        PanelAction::LaunchInTerminal => "utilities-terminal",
+        PanelAction::LaunchOnDiscreteGpu => "video-display",
+        PanelAction::OpenDesktopFile => "text-x-generic",
+        PanelAction::CopyCommandLine | PanelAction::Copy(_) => "edit-copy",
+        PanelAction::Hide => "list-remove",
+        PanelAction::Pin => "list-add",
+        PanelAction::Confirm => "system-shutdown",
+    }
+}
+
 pub fn render_icon(entry: &Entry, cfg: Rc<Config>) -> Image {
     match &entry.kind {
         EntryKind::App(appinfo) => create_app_icon_widget(appinfo, cfg),
         EntryKind::Action(_) => create_generic_icon_widget("system-shutdown", cfg),
         EntryKind::Result(_) => create_generic_icon_widget("accessories-calculator", cfg),
+        EntryKind::Hint => create_generic_icon_widget("dialog-information", cfg),
+        EntryKind::File(path) => create_file_icon_widget(path, cfg),
+        EntryKind::Recent(doc) => create_content_type_icon_widget(&doc.mime_type, cfg),
+        EntryKind::OpenWith { app, .. } | EntryKind::AppWithArgs { app, .. } => create_app_icon_widget(app, cfg),
+        EntryKind::Command { .. } => create_generic_icon_widget("utilities-terminal", cfg),
+        EntryKind::Executable(_) => create_generic_icon_widget("application-x-executable", cfg),
+        EntryKind::Url(_) => create_generic_icon_widget("web-browser", cfg),
+        EntryKind::Ssh(_) => create_generic_icon_widget("network-server", cfg),
+        EntryKind::Emoji { .. } => create_generic_icon_widget("face-smile", cfg),
+        EntryKind::Clipboard(_) => create_generic_icon_widget("edit-paste", cfg),
+        EntryKind::Window(window) => create_window_icon_widget(window, cfg),
+        EntryKind::Process(_) => create_generic_icon_widget("utilities-system-monitor", cfg),
+        EntryKind::Signal { .. } => create_generic_icon_widget("process-stop", cfg),
+        EntryKind::Unit(_) => create_generic_icon_widget("system-run", cfg),
+        EntryKind::UnitAction { action: UnitAction::Logs, .. } => create_generic_icon_widget("text-x-log", cfg),
+        EntryKind::UnitAction { .. } => create_generic_icon_widget("system-run", cfg),
+        EntryKind::Script(item) => match &item.icon {
+            Some(icon) => create_named_icon_widget(icon, "application-x-executable", cfg),
+            None => create_generic_icon_widget("application-x-executable", cfg),
+        },
+        EntryKind::Plugin(item) => match &item.icon {
+            Some(icon) => create_named_icon_widget(icon, "application-x-addon", cfg),
+            None => create_generic_icon_widget("application-x-addon", cfg),
+        },
+        EntryKind::PanelAction { action, .. } => create_generic_icon_widget(panel_icon(action), cfg),
+        EntryKind::Cancel => create_generic_icon_widget("go-previous", cfg),
     }
 }
 
-pub fn render_row(entry: &Entry, cfg: Rc<Config>) -> ListBoxRow {
+pub fn render_row(entry: &Entry, cfg: Rc<Config>, plugins: &Plugins) -> ListBoxRow {
     let row = ListBoxRow::new();
     let hbox = GtkBox::new(Orientation::Horizontal, 8);
 
+    // Plugins may draw their rows themselves.
+    if let EntryKind::Plugin(item) = &entry.kind
+        && plugins.render_row(item, hbox.as_ptr().cast())
+    {
+        row.add(&hbox);
+        return row;
+    }
+
     if cfg.show_icons {
-        let icon = render_icon(entry, cfg);
-        hbox.pack_start(&icon, false, false, 0);
+        // Emoji are their own icon.
+        if let EntryKind::Emoji { glyph, .. } = &entry.kind {
+            hbox.pack_start(&render_glyph(glyph, cfg.icon_size), false, false, 0);
+        } else {
+            let icon = render_icon(entry, cfg);
+            hbox.pack_start(&icon, false, false, 0);
+        }
     }
 
+    let text = GtkBox::new(Orientation::Vertical, 0);
+    text.set_valign(gtk::Align::Center);
+
     let label = Label::new(Some(&entry.title));
     label.set_xalign(0.0);
-    hbox.pack_start(&label, true, true, 0);
+    label.set_ellipsize(gtk::pango::EllipsizeMode::Middle);
+    if matches!(entry.kind, EntryKind::Hint) {
+        label.style_context().add_class("dim-label");
+    }
+    text.pack_start(&label, false, false, 0);
+
+    if let Some(subtitle) = &entry.subtitle {
+        let sublabel = Label::new(Some(subtitle));
+        sublabel.set_xalign(0.0);
+        sublabel.set_ellipsize(gtk::pango::EllipsizeMode::Middle);
+        sublabel.style_context().add_class("dim-label");
+        sublabel.style_context().add_class("subtitle");
+        text.pack_start(&sublabel, false, false, 0);
+    }
+
+    hbox.pack_start(&text, true, true, 0);
 
     row.add(&hbox);
     row
 }
 
+/// A glyph drawn in the icon slot, sized to match the other rows' icons.
+fn render_glyph(glyph: &str, icon_size: i32) -> Label {
+    let label = Label::new(Some(glyph));
+    let attrs = gtk::pango::AttrList::new();
+    attrs.insert(gtk::pango::AttrSize::new_size_absolute(icon_size * 3 / 4 * gtk::pango::SCALE));
+    label.set_attributes(Some(&attrs));
+    label.set_size_request(icon_size, icon_size);
+    label
+}
+
 pub fn install_global_css(css: &str) {
     // Create provider + load CSS
     let provider = CssProvider::new();
@@ -318,9 +531,18 @@ pub fn css_from_config(theme: &Theme) -> String {
         row:selected {{
             background-color: {selection_color};
         }}
+
+        .dim-label {{
+            opacity: 0.55;
+        }}
+
+        label.subtitle {{
+            font-size: {subtitle_font_size}pt;
+        }}
         "#,
         font_family = theme.font_family,
         font_size = theme.font_size,
+        subtitle_font_size = theme.font_size.saturating_sub(3).max(6),
         bg_color = theme.bg_color,
         font_color = theme.font_color,
         selection_color = theme.selection_color,